@@ -1,10 +1,34 @@
 import { basename, resolve } from 'node:path';
 import { prepareBinding, prepareTypescriptBinding } from './bindings.ts';
 import { ensureDir, writeFileSafe } from './fs.ts';
-import { wasmGenerate, wasmInit } from './rs.ts';
+import { type WasmGenerateActorOptions, wasmGenerate, wasmInit } from './rs.ts';
 
 const DID_FILE_EXTENSION = '.did';
 
+/**
+ * A user-defined TypeScript representation of a named Candid type.
+ */
+export type GenerateTypeOverride = {
+  /**
+   * The name of the TypeScript type, exported by `module`.
+   */
+  type: string;
+  /**
+   * The module to import the type and the converter functions from, relative to the generated files.
+   */
+  module: string;
+  /**
+   * The name of the function, exported by `module`, that converts a value of the TypeScript type
+   * to the Candid value expected by the declarations (e.g. `bigint` for `nat64`).
+   */
+  toCandid: string;
+  /**
+   * The name of the function, exported by `module`, that converts the Candid value returned by the declarations
+   * to a value of the TypeScript type.
+   */
+  fromCandid: string;
+};
+
 /**
  * Options for controlling the generated output files.
  */
@@ -38,6 +62,253 @@ export type GenerateOutputOptions = {
          * @default false
          */
         interfaceFile?: boolean;
+        /**
+         * If `true`, the absent optional record fields are omitted from the returned objects instead of
+         * being set to `undefined`, and a missing (`undefined`) or `null` optional field is sent as an
+         * absent value. Falsy values such as `0`, `""` or `false` are sent as present values.
+         *
+         * For `opt opt T` fields, only a missing field is sent as absent, while `null` is the inner absent value.
+         *
+         * @default false
+         */
+        optionalFields?: boolean;
+        /**
+         * Represents association lists (`vec record { K; V }`) as `Map<K, V>` instead of `Array<[K, V]>`.
+         * The generated conversion functions build the `Map` from the Candid value and flatten it back.
+         * Only the keys represented by primitive values (e.g. `text` or `nat`) are supported, since a `Map`
+         * compares the object keys (e.g. `Principal` or `Uint8Array`) by identity.
+         *
+         * If `true`, applies to every association list. If an array of type names, applies only to the
+         * named types (e.g. `['Metadata']` for `type Metadata = vec record { text; Value }`).
+         *
+         * @default false
+         */
+        mapTypes?: boolean | string[];
+        /**
+         * If `true`, emits camelCase names for record fields, method names and method parameters
+         * (e.g. `display_name` becomes `displayName`). The generated conversion functions map
+         * the names back to the original Candid names.
+         *
+         * The generation fails if two Candid names of the same record or service map to the same camelCase name,
+         * including the `_<hash>_` keys of the hashed labels, or if a method maps to a member of the actor class
+         * (e.g. `with_options` to `withOptions`).
+         *
+         * @default false
+         */
+        camelCase?: boolean;
+        /**
+         * Maps named Candid types to user-defined TypeScript types, keyed by the Candid type name.
+         * The generated API uses the user-defined type and calls the user-defined converter functions
+         * instead of the default conversions.
+         *
+         * The generation fails if a key is not a named type of the Candid file, or if the type and the
+         * converter functions are not identifiers.
+         *
+         * @example
+         *
+         * ```ts
+         * typeOverrides: {
+         *   Timestamp: {
+         *     type: 'DateTime',
+         *     module: '../utils/timestamp',
+         *     toCandid: 'dateTimeToNanos',
+         *     fromCandid: 'nanosToDateTime',
+         *   },
+         * }
+         * ```
+         */
+        typeOverrides?: Record<string, GenerateTypeOverride>;
+        /**
+         * If `true`, recognizes the well-known types of the ICRC-1, ICRC-2 and ICRC-3 token standards
+         * (e.g. `Account`, `TransferError`, `Value`) by their structure, and represents them with the types
+         * of a shared `icrc.ts` module generated in `outDir`. The module also exports the `encodeAccount` and
+         * `decodeAccount` helpers for the ICRC-1 textual encoding of accounts.
+         *
+         * The options shaping the ICRC types are part of the name of the module (e.g. `icrc-camel.ts` with
+         * `camelCase`), so that the services generated with different options do not share it. A service
+         * named `icrc` is rejected.
+         *
+         * If the service implements all the methods of ICRC-1 or ICRC-2, its interface extends
+         * `icrc.Icrc1Interface` or `icrc.Icrc2Interface`.
+         *
+         * The shared module has no lenient input types nor branded types: with `inputTypes` or
+         * `brandedTypes`, the ICRC types are generated in the bindings, like without this option.
+         *
+         * @default false
+         */
+        icrcTypes?: boolean;
+        /**
+         * If `true`, method arguments accept lenient input types: `number` for `bigint` values
+         * (only safe integers, larger numbers throw a `RangeError`), the principal text for `Principal` values and `number[]` for `blob` values. The generated
+         * conversion functions normalize the inputs, while the results keep the strict types.
+         *
+         * Each named type `X` that accepts lenient inputs gets an `XInput` type alias (`Input<X>`).
+         *
+         * @default false
+         */
+        inputTypes?: boolean;
+        /**
+         * If `true`, methods with multiple return values return an object keyed by the Candid names
+         * of the return values (e.g. `{ count, items }` for `-> (count : nat, items : vec Item)`)
+         * instead of a tuple. Unnamed return values are keyed by their position (e.g. `_0`).
+         *
+         * @default false
+         */
+        namedReturns?: boolean;
+        /**
+         * If `true`, methods with more than one argument take a single object keyed by the Candid
+         * names of the arguments (e.g. `transfer({ to, amount })` for `transfer : (to : Account, amount : nat) -> ()`)
+         * instead of positional arguments. Unnamed arguments are keyed by their position (e.g. `arg0`).
+         *
+         * @default false
+         */
+        namedArgs?: boolean;
+        /**
+         * If `true`, aliases of primitive types (e.g. `type CanisterId = principal;`) are represented
+         * as branded types (e.g. `Principal & { readonly __brand: "CanisterId" }`), so that values of
+         * different aliases cannot be mixed up. Each branded type gets a constructor of the same name
+         * (e.g. `CanisterId(principal)`), and the values returned by the methods are branded.
+         *
+         * @default false
+         */
+        brandedTypes?: boolean;
+        /**
+         * If `true`, the generated types are immutable: record properties and tuples are `readonly`,
+         * arrays are `ReadonlyArray` and maps are `ReadonlyMap`.
+         *
+         * @default false
+         */
+        readonlyTypes?: boolean;
+        /**
+         * If `true`, the records, tuples, variants and arrays returned by the methods are frozen
+         * with `Object.freeze`. Typed arrays (e.g. `Uint8Array`) and maps are not frozen.
+         * Best used together with `readonlyTypes`.
+         *
+         * @default false
+         */
+        freezeResults?: boolean;
+        /**
+         * A dictionary of known record field names. Record fields with hashed labels
+         * (e.g. `42 : nat` or `0x2a : nat`) whose hash matches one of these names are named after
+         * it instead of being keyed as `_42_`. The conversion functions map between the names and
+         * the hashed keys of the declarations.
+         *
+         * @default []
+         */
+        labelNames?: string[];
+        /**
+         * The representation of principals (and service references):
+         * - `'object'`: `Principal` objects from `@icp-sdk/core/principal`.
+         * - `'text'`: the principal text (e.g. `'aaaaa-aa'`). Inputs are parsed with `Principal.fromText`,
+         *   which throws on invalid principals, and results are converted with `toText`.
+         *
+         * @default 'object'
+         */
+        principalFormat?: 'object' | 'text';
+        /**
+         * If `true`, service references are typed as `ServiceRef<T>`, where `T` is the interface of the service,
+         * instead of a bare `Principal`. The `fromServiceRef` helper creates an actor for a received reference,
+         * and the `ServiceRef` constructor creates a reference from a principal.
+         *
+         * @default false
+         */
+        serviceRefs?: boolean;
+        /**
+         * If `true`, a callable constructor is generated for each named function type (e.g. streaming callbacks).
+         * Given a function reference and an agent, it returns a function that calls the referenced method,
+         * with the IDL of the function type exported by the declarations as `idlReferenceTypes`.
+         *
+         * @default false
+         */
+        funcRefs?: boolean;
+        /**
+         * If `true`, a wrapper class and a `create<Name>Actor` function are generated for each named service type,
+         * not only for the main actor, e.g. to call the services received by reference.
+         *
+         * @default false
+         */
+        serviceClasses?: boolean;
+        /**
+         * If `true`, the call mode of each method (`query`, `composite_query`, `update` or `oneway`)
+         * is exposed as a JSDoc tag on the interface methods and in a `static readonly methodModes` table
+         * on the actor classes, e.g. to choose the caching and loading behavior per method.
+         *
+         * @default false
+         */
+        methodModes?: boolean;
+        /**
+         * If `true`, the `oneway` methods are submitted without waiting for the canister,
+         * and resolve to a `OnewayReceipt` with the request id once the call is submitted.
+         * If the call cannot be submitted, they reject with a `OnewaySubmissionError`.
+         *
+         * @default false
+         */
+        onewayReceipts?: boolean;
+        /**
+         * If `true`, the actors get a `certified` property, an actor of the same interface
+         * that calls the query methods as update calls, whose responses are certified.
+         * Useful for security-sensitive reads, e.g. `await actor.certified.balance()`.
+         *
+         * @default false
+         */
+        certifiedQueries?: boolean;
+        /**
+         * If `true`, the actors get a `withOptions(options)` method, returning an actor of the same interface
+         * whose calls are made with the given `CallOptions`: an `AbortSignal`, the effective canister id,
+         * the polling strategy of the update calls or a different agent (e.g. with another identity).
+         *
+         * @default false
+         */
+        callOptions?: boolean;
+        /**
+         * If `true`, the methods returning a `variant { Ok : T; Err : E }` get an `...OrThrow` variant
+         * (e.g. `transferOrThrow`), which returns the `Ok` payload directly and throws a `<Service>Error`
+         * carrying the `Err` payload and the method name otherwise. The `@throws` tag of each method
+         * gives the type of its `Err` payload. The variants whose `Ok` and `Err` are both `null` are
+         * represented as enums, and get no `...OrThrow` variant.
+         *
+         * @default false
+         */
+        orThrowMethods?: boolean;
+        /**
+         * If `true`, the methods translate the reject errors of the agent into a `CanisterTrapError`
+         * if the canister trapped, or a `CanisterRejectError` otherwise (e.g. if the canister is out of cycles),
+         * with the method name, the reject code and the reject message. Other errors are thrown unchanged.
+         *
+         * @default false
+         */
+        canisterErrors?: boolean;
+        /**
+         * If `true`, `createActor` accepts a chain of `interceptors`, whose `before(method, args)`, `after(method, result)`
+         * and `onError(method, error)` hooks are invoked around the calls. The `method` has the `name` and the `mode`
+         * (e.g. `query`) of the called method, the arguments and the results are converted to Candid.
+         *
+         * @default false
+         */
+        interceptors?: boolean;
+        /**
+         * If `true`, `createActor` accepts a `retryPolicy` (attempts, backoff and jitter), which retries the calls
+         * failing with a network error: a rejection of `fetch`, or an HTTP error of the replica with a 5xx or 429 status.
+         * Only the query methods and the update methods marked as idempotent are retried,
+         * either listed in `idempotentMethods` or annotated with `@idempotent` in their doc comment.
+         *
+         * @default false
+         */
+        retryPolicy?: boolean;
+        /**
+         * The update methods that can be retried by the `retryPolicy`, in addition to the methods annotated with `@idempotent`.
+         *
+         * @default []
+         */
+        idempotentMethods?: string[];
+        /**
+         * If `true`, `createActor` accepts a `queryCache` (TTL and max size), which caches the responses of the query
+         * and composite query methods, keyed on the method name and the Candid-encoded arguments.
+         * The actors get an `invalidate(methodName?)` method removing the cached responses of a method, or of all the methods.
+         *
+         * @default false
+         */
+        queryCache?: boolean;
       };
   /**
    * Options for controlling the generated declarations files.
@@ -143,6 +414,7 @@ export async function generate(options: GenerateOptions) {
       root_exports: declarationsRootExports,
       typescript: declarationsTypescript,
     },
+    actor: actorGenerateOptions(output.actor),
   });
 
   // Extract all strings from the WASM object synchronously before any async
@@ -157,6 +429,8 @@ export async function generate(options: GenerateOptions) {
     declarations_typescript: result.declarations_typescript,
     interface_ts: result.interface_ts,
     service_ts: result.service_ts,
+    icrc_ts: result.icrc_ts,
+    icrc_module: result.icrc_module,
   };
   result.free();
 
@@ -170,12 +444,58 @@ export async function generate(options: GenerateOptions) {
   });
 }
 
+function actorGenerateOptions(actor: GenerateOutputOptions['actor']): WasmGenerateActorOptions {
+  if (actor?.disabled) {
+    return {};
+  }
+
+  const mapTypes = actor?.mapTypes ?? false;
+
+  return {
+    optional_fields: actor?.optionalFields ?? false,
+    map_types: mapTypes === true,
+    map_type_names: Array.isArray(mapTypes) ? mapTypes : [],
+    camel_case: actor?.camelCase ?? false,
+    type_overrides: Object.entries(actor?.typeOverrides ?? {}).map(([name, typeOverride]) => ({
+      name,
+      type_name: typeOverride.type,
+      module: typeOverride.module,
+      to_candid: typeOverride.toCandid,
+      from_candid: typeOverride.fromCandid,
+    })),
+    icrc_types: actor?.icrcTypes ?? false,
+    input_types: actor?.inputTypes ?? false,
+    named_returns: actor?.namedReturns ?? false,
+    named_args: actor?.namedArgs ?? false,
+    branded_types: actor?.brandedTypes ?? false,
+    readonly_types: actor?.readonlyTypes ?? false,
+    freeze_results: actor?.freezeResults ?? false,
+    label_names: actor?.labelNames ?? [],
+    principal_text: actor?.principalFormat === 'text',
+    service_refs: actor?.serviceRefs ?? false,
+    func_refs: actor?.funcRefs ?? false,
+    service_classes: actor?.serviceClasses ?? false,
+    method_modes: actor?.methodModes ?? false,
+    oneway_receipts: actor?.onewayReceipts ?? false,
+    certified_queries: actor?.certifiedQueries ?? false,
+    call_options: actor?.callOptions ?? false,
+    or_throw_methods: actor?.orThrowMethods ?? false,
+    canister_errors: actor?.canisterErrors ?? false,
+    interceptors: actor?.interceptors ?? false,
+    retry_policy: actor?.retryPolicy ?? false,
+    idempotent_methods: actor?.idempotentMethods ?? [],
+    query_cache: actor?.queryCache ?? false,
+  };
+}
+
 type Bindings = {
   declarations_js: string;
   declarations_ts: string;
   declarations_typescript: string;
   interface_ts: string;
   service_ts: string;
+  icrc_ts: string;
+  icrc_module: string;
 };
 
 type WriteBindingsOptions = {
@@ -229,6 +549,14 @@ async function writeBindings({
     );
     await writeFileSafe(interfaceTsFile, interfaceTs, force);
   }
+
+  // The ICRC module is shared by all the services generated in the same directory with the same options,
+  // its name depends on the options shaping the ICRC types (e.g. `icrc-camel.ts`).
+  // It is always overwritten, so that a module left by an older version of the generator is never kept.
+  if (bindings.icrc_ts) {
+    const icrcTsFile = resolve(outDir, `${bindings.icrc_module}.ts`);
+    await writeFileSafe(icrcTsFile, prepareBinding(bindings.icrc_ts), true);
+  }
 }
 
 // The WASM generator always emits imports as './declarations/<name>.did'.
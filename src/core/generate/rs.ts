@@ -1,4 +1,5 @@
 import type {
+  GenerateActorOptions,
   GenerateDeclarationsOptions,
   GenerateOptions,
   GenerateResult,
@@ -33,6 +34,7 @@ export function wasmInit(...args: Parameters<typeof init>): Promise<void> {
 
 export const wasmStart = start;
 export const wasmGenerate = generate;
+export type WasmGenerateActorOptions = GenerateActorOptions;
 export type WasmGenerateDeclarationsOptions = GenerateDeclarationsOptions;
 export type WasmGenerateOptions = GenerateOptions;
 export type WasmGenerateResult = GenerateResult;
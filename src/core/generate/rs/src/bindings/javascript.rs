@@ -356,14 +356,139 @@ fn pp_actor<'a>(ty: &'a Type, recs: &'a BTreeSet<&'a str>) -> RcDoc<'a> {
     }
 }
 
+/// Returns the named service and function types, in the order of the type environment.
+pub(crate) fn reference_type_names(env: &TypeEnv) -> Vec<&str> {
+    env.to_sorted_iter()
+        .filter(|(_, ty)| matches!(ty.as_ref(), TypeInner::Service(_) | TypeInner::Func(_)))
+        .map(|(id, _)| id.as_str())
+        .collect()
+}
+
+/// Returns the IDL class of a named service or function type in TypeScript, e.g. `IDL.FuncClass`.
+pub(crate) fn reference_type_class(env: &TypeEnv, id: &str) -> &'static str {
+    match env.find_type(&id.into()).map(|ty| ty.as_ref()) {
+        Ok(TypeInner::Service(_)) => "IDL.ServiceClass",
+        _ => "IDL.FuncClass",
+    }
+}
+
+/// Renders the `idlReferenceTypes` factory, which returns the IDL types of the named services and
+/// functions, e.g. to call a function reference with the IDL of its type.
+fn compile_reference_types(env: &TypeEnv, typed: bool) -> String {
+    let names = reference_type_names(env);
+    let tys: Vec<Type> = names
+        .iter()
+        .map(|id| TypeInner::Var((*id).into()).into())
+        .collect();
+    let mut def_list = chase_types(env, &tys).unwrap();
+    let recs_owned = infer_and_optimize_recs(env, &mut def_list);
+    let recs: BTreeSet<&str> = recs_owned.iter().map(|s| s.as_str()).collect();
+
+    let types = names.iter().map(|&id| {
+        let value = if recs.contains(id) {
+            // The recursive types are `IDL.Rec()` placeholders, return the filled types
+            let value = ident(id).append(".getType()");
+            if typed {
+                value.append(" as ").append(reference_type_class(env, id))
+            } else {
+                value
+            }
+        } else {
+            ident(id)
+        };
+        quote_ident(id).append(kwd(":")).append(value)
+    });
+    let body = pp_defs(env, &def_list, &recs, false)
+        .append(kwd("return"))
+        .append(sep_enclose_space(types, ",", "{", "}"))
+        .append(";");
+    let header = if typed {
+        str("export const idlReferenceTypes: ")
+            .append(pp_reference_types_type(env))
+            .append(" = ({ IDL }) => ")
+    } else {
+        str("export const idlReferenceTypes = ({ IDL }) => ")
+    };
+    header
+        .append(enclose_space("{", body, "};"))
+        .append(RcDoc::hardline())
+        .append(RcDoc::hardline())
+        .append(pp_service_factories(env, typed))
+        .pretty(LINE_WIDTH)
+        .to_string()
+}
+
+/// Returns the named service types, in the order of the type environment.
+pub(crate) fn service_type_names(env: &TypeEnv) -> Vec<&str> {
+    reference_type_names(env)
+        .into_iter()
+        .filter(|id| reference_type_class(env, id) == "IDL.ServiceClass")
+        .collect()
+}
+
+/// Renders the `idlFactories` object, which holds an interface factory for each named service type,
+/// e.g. to create an actor of a service received by reference.
+fn pp_service_factories(env: &TypeEnv, typed: bool) -> RcDoc<'_> {
+    let factories = service_type_names(env).into_iter().map(|id| {
+        quote_ident(id)
+            .append(kwd(":"))
+            .append("({ IDL }) => idlReferenceTypes({ IDL })")
+            .append(
+                if id.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                    && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    str(".").append(id)
+                } else {
+                    str("['").append(id).append("']")
+                },
+            )
+    });
+    let header = if typed {
+        str("export const idlFactories: ")
+            .append(pp_service_factories_type(env))
+            .append(" = ")
+    } else {
+        str("export const idlFactories = ")
+    };
+    header
+        .append(sep_enclose_space(factories, ",", "{", "}"))
+        .append(";")
+}
+
+/// Returns the type of the `idlFactories` object, e.g. `{ 'broker' : IDL.InterfaceFactory }`.
+pub(crate) fn pp_service_factories_type(env: &TypeEnv) -> RcDoc<'_> {
+    let types = service_type_names(env).into_iter().map(|id| {
+        quote_ident(id)
+            .append(kwd(":"))
+            .append("IDL.InterfaceFactory")
+    });
+    sep_enclose_space(types, ",", "{", "}")
+}
+
+/// Returns the type of the `idlReferenceTypes` factory,
+/// e.g. `(args: { IDL: typeof IDL }) => { 'f' : IDL.FuncClass }`.
+pub(crate) fn pp_reference_types_type(env: &TypeEnv) -> RcDoc<'_> {
+    let types = reference_type_names(env).into_iter().map(|id| {
+        quote_ident(id)
+            .append(kwd(":"))
+            .append(reference_type_class(env, id))
+    });
+    str("(args: { IDL: typeof IDL }) => ").append(sep_enclose_space(types, ",", "{", "}"))
+}
+
 fn pp_imports<'a>() -> RcDoc<'a> {
     str("import { IDL } from '@icp-sdk/core/candid';")
         .append(RcDoc::hardline())
         .append(RcDoc::hardline())
 }
 
-pub fn compile(env: &TypeEnv, actor: &Option<Type>, root_exports: bool) -> String {
-    match actor {
+pub fn compile(
+    env: &TypeEnv,
+    actor: &Option<Type>,
+    root_exports: bool,
+    reference_types: bool,
+) -> String {
+    let declarations = match actor {
         None => {
             let mut def_list: Vec<_> = env.to_sorted_iter().map(|pair| pair.0.as_str()).collect();
             let initial_recs = infer_rec(env, &def_list).unwrap();
@@ -434,6 +559,16 @@ pub fn compile(env: &TypeEnv, actor: &Option<Type>, root_exports: bool) -> Strin
 
             result.pretty(LINE_WIDTH).to_string()
         }
+    };
+
+    if reference_types {
+        format!(
+            "{}\n\n{}",
+            declarations,
+            compile_reference_types(env, false)
+        )
+    } else {
+        declarations
     }
 }
 
@@ -444,6 +579,7 @@ pub fn compile_typescript(
     actor: &Option<Type>,
     prog: &IDLMergedProg,
     root_exports: bool,
+    reference_types: bool,
 ) -> String {
     use super::typescript;
 
@@ -550,5 +686,14 @@ pub fn compile_typescript(
         }
     };
 
-    format!("{}\n{}\n", ts_prefix, js_code)
+    if reference_types {
+        format!(
+            "{}\n{}\n\n{}\n",
+            ts_prefix,
+            js_code,
+            compile_reference_types(env, true)
+        )
+    } else {
+        format!("{}\n{}\n", ts_prefix, js_code)
+    }
 }
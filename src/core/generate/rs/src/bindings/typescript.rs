@@ -1,7 +1,9 @@
 //! Ported from https://github.com/dfinity/candid/blob/1ddf879f368f765145223c08bbe2c8c8f4782dcc/rust/candid_parser/src/bindings/typescript.rs
 
 use super::comments::escape_doc_comment;
-use super::javascript::{ident, is_tuple_fields};
+use super::javascript::{
+    ident, is_tuple_fields, pp_reference_types_type, pp_service_factories_type,
+};
 use candid::pretty::utils::*;
 use candid::types::{Field, Function, Label, SharedLabel, Type, TypeEnv, TypeInner};
 use candid_parser::syntax::{self, IDLMergedProg, IDLType};
@@ -340,6 +342,7 @@ pub fn compile(
     actor: &Option<Type>,
     prog: &IDLMergedProg,
     root_exports: bool,
+    reference_types: bool,
 ) -> String {
     let header = r#"import type { ActorMethod } from '@icp-sdk/core/agent';
 import type { IDL } from '@icp-sdk/core/candid';
@@ -373,9 +376,22 @@ import type { Principal } from '@icp-sdk/core/principal';
                 .append("export declare const init: (args: { IDL: typeof IDL }) => IDL.Type[];")
         }
     };
+    let reference_types = if reference_types {
+        RcDoc::line()
+            .append("export declare const idlReferenceTypes: ")
+            .append(pp_reference_types_type(env))
+            .append(";")
+            .append(RcDoc::line())
+            .append("export declare const idlFactories: ")
+            .append(pp_service_factories_type(env))
+            .append(";")
+    } else {
+        RcDoc::nil()
+    };
     let doc = RcDoc::text(header)
         .append(RcDoc::line())
         .append(defs)
-        .append(actor);
+        .append(actor)
+        .append(reference_types);
     doc.pretty(LINE_WIDTH).to_string()
 }
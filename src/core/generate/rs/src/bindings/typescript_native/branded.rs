@@ -0,0 +1,166 @@
+use super::conversion_functions_generator::TopLevelNodes;
+use super::new_typescript_native_types::convert_type;
+use super::utils::get_ident_guarded;
+use candid::types::{Type, TypeEnv, TypeInner};
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use crate::GenerateActorOptions;
+
+/// The property of the branded types that makes them nominal.
+const BRAND_PROPERTY: &str = "__brand";
+
+/// Whether the named type `id` is represented as a branded type: an alias of a primitive type,
+/// e.g. `type CanisterId = principal;`, when branded types are enabled.
+pub fn is_branded(env: &TypeEnv, options: &GenerateActorOptions, id: &str) -> bool {
+    if !options.branded_types || options.type_override(id).is_some() {
+        return false;
+    }
+    env.find_type(&id.into()).is_ok_and(|ty| {
+        matches!(
+            ty.as_ref(),
+            TypeInner::Bool
+                | TypeInner::Nat
+                | TypeInner::Int
+                | TypeInner::Nat8
+                | TypeInner::Nat16
+                | TypeInner::Nat32
+                | TypeInner::Nat64
+                | TypeInner::Int8
+                | TypeInner::Int16
+                | TypeInner::Int32
+                | TypeInner::Int64
+                | TypeInner::Float32
+                | TypeInner::Float64
+                | TypeInner::Text
+                | TypeInner::Principal
+        )
+    })
+}
+
+/// Creates the branded type of the named type `id`, e.g. `Principal & { readonly __brand: "CanisterId" }`.
+pub fn create_branded_type(
+    top_level_nodes: &mut TopLevelNodes,
+    env: &TypeEnv,
+    id: &str,
+    ty: &Type,
+) -> TsType {
+    let brand = TsType::TsTypeLit(TsTypeLit {
+        span: DUMMY_SP,
+        members: vec![TsTypeElement::TsPropertySignature(TsPropertySignature {
+            span: DUMMY_SP,
+            readonly: true,
+            key: Box::new(Expr::Ident(Ident::new(
+                BRAND_PROPERTY.into(),
+                DUMMY_SP,
+                SyntaxContext::empty(),
+            ))),
+            computed: false,
+            optional: false,
+            type_ann: Some(Box::new(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::new(TsType::TsLitType(TsLitType {
+                    span: DUMMY_SP,
+                    lit: TsLit::Str(Str {
+                        span: DUMMY_SP,
+                        value: id.into(),
+                        raw: None,
+                    }),
+                })),
+            })),
+        })],
+    });
+    TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsIntersectionType(
+        TsIntersectionType {
+            span: DUMMY_SP,
+            types: vec![
+                Box::new(convert_type(top_level_nodes, env, ty, None, false)),
+                Box::new(brand),
+            ],
+        },
+    ))
+}
+
+/// Returns `value as <id>`, which brands a value of the underlying primitive type.
+pub fn brand_value(value: Expr, id: &str) -> Expr {
+    Expr::TsAs(TsAsExpr {
+        span: DUMMY_SP,
+        expr: Box::new(value),
+        type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+            span: DUMMY_SP,
+            type_name: TsEntityName::Ident(get_ident_guarded(id)),
+            type_params: None,
+        })),
+    })
+}
+
+/// Adds a constructor for each branded type, named after the type:
+///
+/// ```ts
+/// export function CanisterId(value: Principal): CanisterId {
+///     return value as CanisterId;
+/// }
+/// ```
+///
+/// Only the signatures are declared if `declare` is set, e.g. in the interface file.
+pub fn add_brand_constructors(
+    top_level_nodes: &mut TopLevelNodes,
+    env: &TypeEnv,
+    module: &mut Module,
+    declare: bool,
+) {
+    let options = top_level_nodes.3;
+    for (id, ty) in env.0.iter() {
+        let id = id.as_str();
+        if !is_branded(env, options, id) {
+            continue;
+        }
+        let value = Ident::new("value".into(), DUMMY_SP, SyntaxContext::empty());
+        let body = (!declare).then(|| BlockStmt {
+            span: DUMMY_SP,
+            stmts: vec![Stmt::Return(ReturnStmt {
+                span: DUMMY_SP,
+                arg: Some(Box::new(brand_value(Expr::Ident(value.clone()), id))),
+            })],
+            ctxt: SyntaxContext::empty(),
+        });
+        let constructor = FnDecl {
+            ident: get_ident_guarded(id),
+            declare,
+            function: Box::new(Function {
+                params: vec![Param {
+                    span: DUMMY_SP,
+                    decorators: vec![],
+                    pat: Pat::Ident(BindingIdent {
+                        id: value,
+                        type_ann: Some(Box::new(TsTypeAnn {
+                            span: DUMMY_SP,
+                            type_ann: Box::new(convert_type(top_level_nodes, env, ty, None, false)),
+                        })),
+                    }),
+                }],
+                decorators: vec![],
+                span: DUMMY_SP,
+                body,
+                is_generator: false,
+                is_async: false,
+                type_params: None,
+                return_type: Some(Box::new(TsTypeAnn {
+                    span: DUMMY_SP,
+                    type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+                        span: DUMMY_SP,
+                        type_name: TsEntityName::Ident(get_ident_guarded(id)),
+                        type_params: None,
+                    })),
+                })),
+                ctxt: SyntaxContext::empty(),
+            }),
+        };
+        module
+            .body
+            .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                span: DUMMY_SP,
+                decl: Decl::Fn(constructor),
+            })));
+    }
+}
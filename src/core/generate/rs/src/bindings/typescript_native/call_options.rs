@@ -0,0 +1,164 @@
+use super::certified::return_wrapped_actor;
+use super::new_typescript_native_types::service_interface_ident;
+use super::utils::get_ident_guarded;
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use super::runtime::Runtime;
+
+/// The name of the method of the actors binding the options of the calls.
+pub const WITH_OPTIONS: &str = "withOptions";
+
+/// The type of the options of the calls.
+const CALL_OPTIONS_TYPE: &str = "CallOptions";
+
+/// The options of the calls of the actors.
+pub const CALL_OPTIONS: Runtime = Runtime {
+    enabled: |options| options.call_options,
+    types: r#"/**
+ * The options of the calls of an actor, see `withOptions`.
+ */
+export interface CallOptions {
+    /**
+     * Stops waiting for the responses of the calls once aborted. The submitted calls are not canceled.
+     */
+    signal?: AbortSignal;
+    /**
+     * The effective canister id of the calls, e.g. for the calls to the management canister.
+     */
+    effectiveCanisterId?: Principal;
+    /**
+     * The polling strategy of the update calls.
+     */
+    pollingOptions?: CallConfig["pollingOptions"];
+    /**
+     * The agent of the calls, e.g. with a different identity.
+     */
+    agent?: Agent;
+}
+"#,
+    classes: &[],
+    implementation: r#"function with_call_options<T>(actor: ActorSubclass<T>, options: CallOptions): ActorSubclass<T> {
+    const { signal, ...config } = options;
+    return new Proxy(actor, {
+        get (target, property, receiver) {
+            const method = Reflect.get(target, property, receiver);
+            // The actors derived from the actor, e.g. its certified actor, get the options too
+            if (method instanceof Actor) {
+                return with_call_options(method as ActorSubclass<unknown>, options);
+            }
+            if (typeof method !== "function" || !("withOptions" in method)) {
+                return method;
+            }
+            const call = (method as ActorMethod).withOptions(config);
+            return (...args: unknown[])=>signal === undefined ? call(...args) : abortable(call(...args), signal);
+        }
+    });
+}
+function abortable<T>(promise: Promise<T>, signal: AbortSignal): Promise<T> {
+    signal.throwIfAborted();
+    return new Promise((resolve, reject)=>{
+        const abort = ()=>reject(signal.reason);
+        signal.addEventListener("abort", abort, {
+            once: true
+        });
+        promise.then(resolve, reject).finally(()=>signal.removeEventListener("abort", abort));
+    });
+}
+"#,
+};
+
+/// Returns the `withOptions` method of a service interface:
+///
+/// ```ts
+/// withOptions(options: CallOptions): helloInterface;
+/// ```
+pub fn with_options_signature(service_name: &str) -> TsTypeElement {
+    TsTypeElement::TsMethodSignature(TsMethodSignature {
+        span: DUMMY_SP,
+        key: Box::new(Expr::Ident(get_ident_guarded(WITH_OPTIONS))),
+        computed: false,
+        optional: false,
+        params: vec![TsFnParam::Ident(options_param())],
+        type_ann: Some(Box::new(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+                span: DUMMY_SP,
+                type_name: TsEntityName::Ident(service_interface_ident(service_name)),
+                type_params: None,
+            })),
+        })),
+        type_params: None,
+    })
+}
+
+/// Returns the `withOptions` method of an actor class, an actor of the same class
+/// whose calls are made with the given options:
+///
+/// ```ts
+/// withOptions(options: CallOptions): Hello {
+///     return new Hello(with_call_options(this.actor, options));
+/// }
+/// ```
+pub fn with_options_method(class_name: &str) -> ClassMember {
+    let options = Expr::Ident(Ident::new(
+        "options".into(),
+        DUMMY_SP,
+        SyntaxContext::empty(),
+    ));
+    ClassMember::Method(ClassMethod {
+        span: DUMMY_SP,
+        key: PropName::Ident(IdentName::new(WITH_OPTIONS.into(), DUMMY_SP)),
+        function: Box::new(Function {
+            params: vec![Param {
+                span: DUMMY_SP,
+                decorators: vec![],
+                pat: Pat::Ident(options_param()),
+            }],
+            decorators: vec![],
+            span: DUMMY_SP,
+            body: Some(return_wrapped_actor(
+                class_name,
+                "with_call_options",
+                vec![options],
+            )),
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: Some(Box::new(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+                    span: DUMMY_SP,
+                    type_name: TsEntityName::Ident(get_ident_guarded(class_name)),
+                    type_params: None,
+                })),
+            })),
+            ctxt: SyntaxContext::empty(),
+        }),
+        kind: MethodKind::Method,
+        is_static: false,
+        accessibility: None,
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+    })
+}
+
+// options: CallOptions
+fn options_param() -> BindingIdent {
+    BindingIdent {
+        id: Ident::new("options".into(), DUMMY_SP, SyntaxContext::empty()),
+        type_ann: Some(Box::new(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+                span: DUMMY_SP,
+                type_name: TsEntityName::Ident(Ident::new(
+                    CALL_OPTIONS_TYPE.into(),
+                    DUMMY_SP,
+                    SyntaxContext::empty(),
+                )),
+                type_params: None,
+            })),
+        })),
+    }
+}
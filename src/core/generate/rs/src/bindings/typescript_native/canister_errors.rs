@@ -0,0 +1,112 @@
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use super::runtime::{Runtime, RuntimeClass};
+use crate::GenerateActorOptions;
+
+/// The function translating the errors of a call, in the wrapper.
+const WITH_CANISTER_ERRORS: &str = "with_canister_errors";
+
+/// The errors of the canister calls, and their translation in the wrapper.
+pub const CANISTER_ERRORS: Runtime = Runtime {
+    enabled: |options| options.canister_errors,
+    types: "",
+    classes: &[
+        RuntimeClass {
+            docs: r#"/**
+ * Thrown if a call was rejected, e.g. by the canister or because the canister is out of cycles.
+ * See https://internetcomputer.org/docs/references/ic-interface-spec#reject-codes
+ */
+"#,
+            name: "CanisterRejectError",
+            extends: "Error",
+            fields: &[
+                ("methodName", "string"),
+                ("rejectCode", "number"),
+                ("rejectMessage", "string"),
+                ("errorCode", "string | undefined"),
+                ("error", "unknown"),
+            ],
+            message: "The call to ${methodName} was rejected (reject code ${rejectCode}): ${rejectMessage}",
+        },
+        RuntimeClass {
+            docs: r#"/**
+ * Thrown if the canister trapped while processing a call.
+ */
+"#,
+            name: "CanisterTrapError",
+            extends: "CanisterRejectError",
+            fields: &[],
+            message: "",
+        },
+    ],
+    implementation: r#"interface Reject {
+    rejectCode: number;
+    rejectMessage: string;
+    errorCode: string | undefined;
+}
+function reject_of(error: unknown, depth = 0): Reject | undefined {
+    if (typeof error !== "object" || error === null || depth > 4) {
+        return undefined;
+    }
+    const { rejectCode, reject_code, rejectMessage, reject_message, rejectErrorCode, error_code, cause, code } = error as Record<string, unknown>;
+    const reject = {
+        rejectCode: rejectCode ?? reject_code,
+        rejectMessage: rejectMessage ?? reject_message,
+        errorCode: rejectErrorCode ?? error_code
+    };
+    if (typeof reject.rejectCode === "number" && typeof reject.rejectMessage === "string") {
+        return {
+            rejectCode: reject.rejectCode,
+            rejectMessage: reject.rejectMessage,
+            errorCode: typeof reject.errorCode === "string" ? reject.errorCode : undefined
+        };
+    }
+    return reject_of(cause, depth + 1) ?? reject_of(code, depth + 1);
+}
+async function with_canister_errors<T>(methodName: string, call: Promise<T>): Promise<T> {
+    try {
+        return await call;
+    } catch (error) {
+        const reject = reject_of(error);
+        if (reject === undefined) {
+            throw error;
+        }
+        const trapped = reject.errorCode === "IC0502" || reject.errorCode === "IC0503" || /trapped/i.test(reject.rejectMessage);
+        const CanisterError = trapped ? CanisterTrapError : CanisterRejectError;
+        throw new CanisterError(methodName, reject.rejectCode, reject.rejectMessage, reject.errorCode, error);
+    }
+}
+"#,
+};
+
+/// Wraps a call to translate the reject errors of the agent, if enabled:
+///
+/// ```ts
+/// with_canister_errors("greet", this.actor.greet(arg0))
+/// ```
+pub fn with_canister_errors(options: &GenerateActorOptions, method: Expr, call: Expr) -> Expr {
+    if !options.canister_errors {
+        return call;
+    }
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(Expr::Ident(Ident::new(
+            WITH_CANISTER_ERRORS.into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )))),
+        args: vec![
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(method),
+            },
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(call),
+            },
+        ],
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    })
+}
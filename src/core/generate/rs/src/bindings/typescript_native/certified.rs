@@ -0,0 +1,205 @@
+use super::new_typescript_native_types::service_interface_ident;
+use super::utils::get_ident_guarded;
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use super::runtime::Runtime;
+
+/// The name of the property of the actors calling the query methods as update calls.
+pub const CERTIFIED: &str = "certified";
+
+/// The actors calling the query methods as update calls.
+pub const CERTIFIED_QUERIES: Runtime = Runtime {
+    enabled: |options| options.certified_queries,
+    types: "",
+    classes: &[],
+    implementation: r#"const CERTIFIED_ACTOR = Symbol("certified_actor");
+function certified_idl_factory(idlFactory: IDL.InterfaceFactory): IDL.InterfaceFactory {
+    return ({ IDL })=>{
+        // Keep the methods of the services, to redeclare the methods of the actor without their query annotations
+        const services = new Map<IDL.ServiceClass, Record<string, IDL.FuncClass>>();
+        const service = idlFactory({
+            IDL: {
+                ...IDL,
+                Service: (fields: Record<string, IDL.FuncClass>)=>{
+                    const service = IDL.Service(fields);
+                    services.set(service, fields);
+                    return service;
+                }
+            }
+        });
+        return IDL.Service(Object.fromEntries(Object.entries(services.get(service) ?? {}).map(([name, func])=>[
+                name,
+                IDL.Func(func.argTypes, func.retTypes, func.annotations.filter((annotation)=>annotation !== "query" && annotation !== "composite_query"))
+            ])));
+    };
+}
+function with_certified_actor<T>(actor: ActorSubclass<T>, certified: ActorSubclass<T>): ActorSubclass<T> {
+    Object.defineProperty(actor, CERTIFIED_ACTOR, {
+        value: certified
+    });
+    Object.defineProperty(certified, CERTIFIED_ACTOR, {
+        value: certified
+    });
+    return actor;
+}
+function certified_actor<T>(actor: ActorSubclass<T>): ActorSubclass<T> {
+    const certified = (actor as ActorSubclass<T> & {
+        [CERTIFIED_ACTOR]?: ActorSubclass<T>;
+    })[CERTIFIED_ACTOR];
+    if (certified === undefined) {
+        throw new Error("The certified actor is only available for the actors created with createActor");
+    }
+    return certified;
+}
+"#,
+};
+
+/// Returns the `certified` property of a service interface:
+///
+/// ```ts
+/// readonly certified: helloInterface;
+/// ```
+pub fn certified_signature(service_name: &str) -> TsTypeElement {
+    TsTypeElement::TsPropertySignature(TsPropertySignature {
+        span: DUMMY_SP,
+        readonly: true,
+        key: Box::new(Expr::Ident(get_ident_guarded(CERTIFIED))),
+        computed: false,
+        optional: false,
+        type_ann: Some(Box::new(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+                span: DUMMY_SP,
+                type_name: TsEntityName::Ident(service_interface_ident(service_name)),
+                type_params: None,
+            })),
+        })),
+    })
+}
+
+/// Returns the `certified` getter of an actor class, an actor of the same class
+/// that calls the query methods as update calls, whose responses are certified:
+///
+/// ```ts
+/// get certified(): Hello {
+///     return new Hello(certified_actor(this.actor));
+/// }
+/// ```
+pub fn certified_getter(class_name: &str) -> ClassMember {
+    ClassMember::Method(ClassMethod {
+        span: DUMMY_SP,
+        key: PropName::Ident(IdentName::new(CERTIFIED.into(), DUMMY_SP)),
+        function: Box::new(Function {
+            params: vec![],
+            decorators: vec![],
+            span: DUMMY_SP,
+            body: Some(return_wrapped_actor(class_name, "certified_actor", vec![])),
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: Some(Box::new(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+                    span: DUMMY_SP,
+                    type_name: TsEntityName::Ident(get_ident_guarded(class_name)),
+                    type_params: None,
+                })),
+            })),
+            ctxt: SyntaxContext::empty(),
+        }),
+        kind: MethodKind::Getter,
+        is_static: false,
+        accessibility: None,
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+    })
+}
+
+/// Returns the body of a member returning an actor of the class `class_name`,
+/// wrapping the actor with the function `wrapper`:
+///
+/// ```ts
+/// return new Hello(wrapper(this.actor, ...args));
+/// ```
+pub fn return_wrapped_actor(class_name: &str, wrapper: &str, args: Vec<Expr>) -> BlockStmt {
+    let this_actor = Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(Expr::This(ThisExpr { span: DUMMY_SP })),
+        prop: MemberProp::Ident(IdentName::new("actor".into(), DUMMY_SP)),
+    });
+    let wrapped_actor = Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(Expr::Ident(Ident::new(
+            wrapper.into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )))),
+        args: std::iter::once(this_actor)
+            .chain(args)
+            .map(|expr| ExprOrSpread {
+                spread: None,
+                expr: Box::new(expr),
+            })
+            .collect(),
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    });
+
+    BlockStmt {
+        span: DUMMY_SP,
+        stmts: vec![Stmt::Return(ReturnStmt {
+            span: DUMMY_SP,
+            arg: Some(Box::new(Expr::New(NewExpr {
+                span: DUMMY_SP,
+                callee: Box::new(Expr::Ident(get_ident_guarded(class_name))),
+                args: Some(vec![ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(wrapped_actor),
+                }]),
+                type_args: None,
+                ctxt: SyntaxContext::empty(),
+            }))),
+        })],
+        ctxt: SyntaxContext::empty(),
+    }
+}
+
+/// Returns the IDL factory of the certified actor created by `createActor`:
+///
+/// ```ts
+/// certified_idl_factory(idlFactory)
+/// ```
+pub fn certified_idl_factory(idl_factory: Expr) -> Expr {
+    call("certified_idl_factory", vec![idl_factory])
+}
+
+/// Binds the certified actor to the actor created by `createActor`, both wrapped the same way:
+///
+/// ```ts
+/// with_certified_actor(actor, certifiedActor)
+/// ```
+pub fn with_certified_actor(actor: Expr, certified: Expr) -> Expr {
+    call("with_certified_actor", vec![actor, certified])
+}
+
+fn call(function: &str, args: Vec<Expr>) -> Expr {
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(Expr::Ident(Ident::new(
+            function.into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )))),
+        args: args
+            .into_iter()
+            .map(|expr| ExprOrSpread {
+                spread: None,
+                expr: Box::new(expr),
+            })
+            .collect(),
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    })
+}
@@ -42,7 +42,7 @@ fn make_comment(docs: &[String]) -> Option<Comment> {
 }
 
 pub fn add_comments(top_level_nodes: &mut TopLevelNodes, docs: &[String]) -> Span {
-    let (_, comments, cursor) = top_level_nodes;
+    let (_, comments, cursor, _) = top_level_nodes;
     match docs.len() {
         0 => DUMMY_SP,
         _ => {
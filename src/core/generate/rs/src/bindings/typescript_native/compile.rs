@@ -3,17 +3,20 @@ use super::compile_wrapper::compile_wrapper;
 use candid::types::{Type, TypeEnv};
 use candid_parser::syntax::IDLMergedProg;
 
+use crate::GenerateActorOptions;
+
 pub fn compile(
     env: &TypeEnv,
     actor: &Option<Type>,
     service_name: &str,
     target: &str,
     prog: &IDLMergedProg,
+    options: &GenerateActorOptions,
 ) -> String {
     if target == "interface" {
-        compile_interface(env, actor, service_name, prog)
+        compile_interface(env, actor, service_name, prog, options)
     } else if target == "wrapper" {
-        compile_wrapper(env, actor, service_name, prog)
+        compile_wrapper(env, actor, service_name, prog, options)
     } else {
         panic!("Invalid target: {}", target);
     }
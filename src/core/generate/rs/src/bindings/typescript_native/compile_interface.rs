@@ -1,12 +1,21 @@
+use super::branded::add_brand_constructors;
+use super::compile_wrapper::{create_actor_function_name, named_service_classes};
 use super::conversion_functions_generator::TypeConverter;
+use super::func_refs::add_func_callable_declarations;
+use super::icrc;
 use super::new_typescript_native_types::{
     add_type_definitions, create_interface_from_service, service_interface_ident,
 };
+use super::or_throw::{error_class_name, or_throw_declarations};
 use super::preamble::imports::interface_imports;
+use super::preamble::input::input_type_utils;
 use super::preamble::options::interface_options_utils;
+use super::preamble::service_ref::{from_service_ref_function, service_ref_type};
+use super::runtime::runtime_declarations;
 use super::utils::EnumDeclarations;
 use super::utils::get_ident_guarded;
 use super::utils::render_ast;
+use crate::GenerateActorOptions;
 use crate::bindings::typescript_native::comments::add_comments;
 use candid::types::{Type, TypeEnv, TypeInner};
 use candid_parser::syntax::{IDLMergedProg, IDLType};
@@ -20,6 +29,7 @@ pub fn compile_interface(
     actor: &Option<Type>,
     service_name: &str,
     prog: &IDLMergedProg,
+    options: &GenerateActorOptions,
 ) -> String {
     let mut enum_declarations: EnumDeclarations = HashMap::new();
 
@@ -29,12 +39,25 @@ pub fn compile_interface(
         shebang: None,
     };
 
-    interface_imports(&mut module, service_name);
+    interface_imports(&mut module, service_name, options);
     interface_options_utils(&mut module);
+    if options.input_types {
+        input_type_utils(&mut module);
+    }
+    if options.service_refs {
+        service_ref_type(&mut module, options, true);
+    }
     let mut comments = swc_core::common::comments::SingleThreadedComments::default();
     let mut cursor = super::comments::PosCursor::new();
-    let mut top_level_nodes = (&mut enum_declarations, &mut comments, &mut cursor);
-    add_type_definitions(&mut top_level_nodes, env, &mut module, prog);
+    let mut top_level_nodes = (&mut enum_declarations, &mut comments, &mut cursor, options);
+    add_type_definitions(
+        &mut top_level_nodes,
+        env,
+        &mut module,
+        prog,
+        &error_class_name(env, service_name),
+    );
+    add_brand_constructors(&mut top_level_nodes, env, &mut module, true);
 
     let mut actor_module = Module {
         span: DUMMY_SP,
@@ -93,12 +116,19 @@ pub fn compile_interface(
     module.body.extend(actor_module.body);
 
     // Add CreateActorOptions interface and createActor function declaration if actor exists
+    add_func_callable_declarations(env, options, &mut module);
+
     if actor.is_some() {
-        add_create_actor_interface_exports(&mut module, service_name);
+        add_create_actor_interface_exports(&mut module, env, service_name, options);
+        if options.service_refs {
+            from_service_ref_function(&mut module, true);
+        }
     }
 
     // Generate code from the AST
     render_ast(&module, &comments)
+        + &or_throw_declarations(env, options, service_name)
+        + &runtime_declarations(options)
 }
 
 fn interface_actor_implementation(
@@ -144,13 +174,18 @@ pub fn interface_actor_service(
     converter: &mut TypeConverter,
     span: Span,
 ) {
-    let interface = create_interface_from_service(
+    let mut interface = create_interface_from_service(
         &mut converter.top_level_nodes(),
         env,
         service_name,
+        &error_class_name(env, service_name),
         syntax,
         serv,
     );
+    interface.extends = icrc::implemented_services(env, serv, converter.options())
+        .iter()
+        .map(|name| icrc::icrc_service_extends(name))
+        .collect();
     module
         .body
         .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
@@ -183,9 +218,15 @@ pub fn interface_actor_var(module: &mut Module, type_id: &str, service_name: &st
         })));
 }
 
-fn add_create_actor_interface_exports(module: &mut Module, service_name: &str) {
+fn add_create_actor_interface_exports(
+    module: &mut Module,
+    env: &TypeEnv,
+    service_name: &str,
+    options: &GenerateActorOptions,
+) {
     // CreateActorOptions interface
-    let create_actor_options_interface = super::preamble::actor::create_actor_options_interface();
+    let create_actor_options_interface =
+        super::preamble::actor::create_actor_options_interface(options);
     module
         .body
         .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
@@ -194,8 +235,31 @@ fn add_create_actor_interface_exports(module: &mut Module, service_name: &str) {
         })));
 
     // createActor function declaration (no implementation)
-    let create_actor_fn_decl = FnDecl {
-        ident: get_ident_guarded("createActor"),
+    module
+        .body
+        .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::Fn(create_actor_declaration("createActor", service_name)),
+        })));
+
+    // create<Name>Actor function declarations of the named services
+    for (id, class_name) in named_service_classes(env, options, service_name) {
+        module
+            .body
+            .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                span: DUMMY_SP,
+                decl: Decl::Fn(create_actor_declaration(
+                    &create_actor_function_name(&class_name),
+                    &id,
+                )),
+            })));
+    }
+}
+
+// function <function_name>(canisterId: string, options: CreateActorOptions = {}): <service_name>Interface
+fn create_actor_declaration(function_name: &str, service_name: &str) -> FnDecl {
+    FnDecl {
+        ident: get_ident_guarded(function_name),
         declare: true,
         function: Box::new(swc_core::ecma::ast::Function {
             params: vec![
@@ -254,12 +318,5 @@ fn add_create_actor_interface_exports(module: &mut Module, service_name: &str) {
             })),
             ctxt: swc_core::common::SyntaxContext::empty(),
         }),
-    };
-
-    module
-        .body
-        .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
-            span: DUMMY_SP,
-            decl: Decl::Fn(create_actor_fn_decl),
-        })));
+    }
 }
@@ -1,4 +1,16 @@
+use super::branded::add_brand_constructors;
+use super::call_options::with_options_method;
+use super::canister_errors::with_canister_errors;
+use super::certified::{certified_getter, certified_idl_factory, with_certified_actor};
 use super::conversion_functions_generator::TypeConverter;
+use super::func_refs::{IDL_FACTORIES, add_func_callables};
+use super::interceptors::with_interceptors;
+use super::method_modes::{method_function, method_modes_table};
+use super::oneway::{
+    create_submit_stmts, is_oneway_submission, oneway_actor_type, oneway_methods,
+    oneway_receipt_type, with_oneway_receipts,
+};
+use super::or_throw::{error_class_name, or_throw_method, or_throw_runtime};
 use super::utils::{contains_unicode_characters, get_ident_guarded, get_ident_guarded_keyword_ok};
 use candid::types::internal::TypeKey;
 use candid::types::{Function, Type, TypeEnv, TypeInner};
@@ -7,15 +19,25 @@ use swc_core::common::{DUMMY_SP, SyntaxContext};
 use swc_core::ecma::ast::*;
 
 use super::conversion_functions_generator::convert_multi_return_from_candid;
-use super::new_typescript_native_types::{convert_type_with_converter, service_interface_ident};
+use super::new_typescript_native_types::{
+    convert_input_type_with_converter, convert_type_with_converter, create_multi_return_type,
+    create_named_args_type, service_interface_ident, uses_named_args,
+};
 
 use super::new_typescript_native_types::add_type_definitions;
 use super::preamble::imports::wrapper_imports;
+use super::preamble::input::{input_type_utils, input_value_utils};
 use super::preamble::options::{interface_options_utils, wrapper_options_utils};
+use super::preamble::service_ref::{from_service_ref_function, service_ref_type};
+use super::query_cache::{invalidate_method, with_query_cache};
+use super::retry::{idempotent_methods, with_retry_policy};
+use super::runtime::runtime_implementations;
 use super::utils::{EnumDeclarations, render_ast};
+use crate::GenerateActorOptions;
 
 use super::comments::add_comments;
 use super::compile_interface::{interface_actor_service, interface_actor_var};
+use super::naming::{argument_names, member_name};
 use candid_parser::syntax::IDLType;
 use std::collections::HashMap;
 use swc_core::common::Span;
@@ -25,6 +47,7 @@ pub fn compile_wrapper(
     actor: &Option<Type>,
     service_name: &str,
     prog: &IDLMergedProg,
+    options: &GenerateActorOptions,
 ) -> String {
     let mut enum_declarations: EnumDeclarations = HashMap::new();
 
@@ -34,14 +57,28 @@ pub fn compile_wrapper(
         shebang: None,
     };
 
-    wrapper_imports(&mut module, service_name);
+    wrapper_imports(&mut module, service_name, options);
     interface_options_utils(&mut module);
-    wrapper_options_utils(&mut module);
+    if options.input_types {
+        input_type_utils(&mut module);
+        input_value_utils(&mut module);
+    }
+    wrapper_options_utils(&mut module, options);
+    if options.service_refs {
+        service_ref_type(&mut module, options, false);
+    }
     // Prepare a shared comments store and cursor if needed by generators
     let mut comments = swc_core::common::comments::SingleThreadedComments::default();
     let mut cursor = super::comments::PosCursor::new();
-    let mut top_level_nodes = (&mut enum_declarations, &mut comments, &mut cursor);
-    add_type_definitions(&mut top_level_nodes, env, &mut module, prog);
+    let mut top_level_nodes = (&mut enum_declarations, &mut comments, &mut cursor, options);
+    add_type_definitions(
+        &mut top_level_nodes,
+        env,
+        &mut module,
+        prog,
+        &error_class_name(env, service_name),
+    );
+    add_brand_constructors(&mut top_level_nodes, env, &mut module, false);
 
     let mut actor_module = Module {
         span: DUMMY_SP,
@@ -57,6 +94,9 @@ pub fn compile_wrapper(
             .unwrap_or(DUMMY_SP);
         {
             let mut converter = TypeConverter::new(env, &mut top_level_nodes);
+            // Before the actor, whose implementation imports the Candid types used so far
+            add_func_callables(env, &mut actor_module, &mut converter);
+            add_service_classes(env, &mut actor_module, service_name, &mut converter);
             wrapper_actor_implementation(
                 env,
                 &mut actor_module,
@@ -98,12 +138,50 @@ pub fn compile_wrapper(
     module.body.extend(actor_module.body);
 
     // Add CreateActorOptions interface and createActor function if actor exists
-    if actor.is_some() {
-        add_create_actor_exports(&mut module, service_name);
+    if let Some(actor_type) = actor {
+        let syntax_actor = prog.resolve_actor().ok().flatten();
+        let idempotent = idempotent_methods(
+            env,
+            options,
+            actor_type,
+            syntax_actor.as_ref().map(|s| &s.typ),
+        );
+        let oneway = actor_service(env, actor_type)
+            .map_or_else(Vec::new, |serv| oneway_methods(env, options, serv));
+        add_create_actor_exports(
+            &mut module,
+            env,
+            prog,
+            service_name,
+            &idempotent,
+            &oneway,
+            options,
+        );
+        if options.service_refs {
+            from_service_ref_function(&mut module, false);
+        }
     }
 
     // Generate code from the AST
     render_ast(&module, &comments)
+        + &or_throw_runtime(env, options, service_name)
+        + &runtime_implementations(options)
+}
+
+/// Returns the methods of the service of the actor.
+pub(super) fn actor_service<'a>(
+    env: &'a TypeEnv,
+    actor_type: &'a Type,
+) -> Option<&'a [(String, Type)]> {
+    match actor_type.as_ref() {
+        TypeInner::Service(serv) => Some(serv),
+        TypeInner::Var(id) => match env.rec_find_type(id).ok()?.as_ref() {
+            TypeInner::Service(serv) => Some(serv),
+            _ => None,
+        },
+        TypeInner::Class(_, t) => actor_service(env, t),
+        _ => None,
+    }
 }
 
 // Add actor implementation
@@ -152,6 +230,8 @@ fn wrapper_actor_service(
         service_name,
         &capitalized_service_name,
         serv,
+        service_type(),
+        &error_class_name(env, service_name),
         converter,
     );
 
@@ -198,6 +278,8 @@ fn wrapper_actor_var(
         service_name,
         &capitalized_service_name,
         serv,
+        service_type(),
+        &error_class_name(env, service_name),
         converter,
     );
     converter.add_import_for_original_type_definitions(module, service_name);
@@ -209,13 +291,104 @@ fn wrapper_actor_var(
         })));
 }
 
+/// Returns the names of the named service types that get their own actor class, with the name of the class.
+/// The classes are named after the services, unless the name clashes with a Candid type or the actor class.
+pub(super) fn named_service_classes(
+    env: &TypeEnv,
+    options: &GenerateActorOptions,
+    service_name: &str,
+) -> Vec<(String, String)> {
+    if !options.service_classes {
+        return vec![];
+    }
+    let actor_class_name = capitalize(service_name);
+    env.0
+        .iter()
+        .filter(|(_, ty)| matches!(ty.as_ref(), TypeInner::Service(_)))
+        .map(|(id, _)| {
+            let class_name = capitalize(id.as_str());
+            let class_name = if class_name == actor_class_name
+                || env.find_type(&class_name.as_str().into()).is_ok()
+            {
+                format!("{}Service", class_name)
+            } else {
+                class_name
+            };
+            (id.as_str().to_string(), class_name)
+        })
+        .collect()
+}
+
+/// Returns the name of the function creating an actor of the class `class_name`, e.g. `createBrokerActor`.
+pub(super) fn create_actor_function_name(class_name: &str) -> String {
+    format!("create{}Actor", class_name)
+}
+
+// Add an actor class for each named service type
+fn add_service_classes(
+    env: &TypeEnv,
+    module: &mut Module,
+    service_name: &str,
+    converter: &mut TypeConverter,
+) {
+    // The `...OrThrow` methods of all the classes throw the error class of the actor
+    let error_class = error_class_name(env, service_name);
+    for (id, class_name) in named_service_classes(env, converter.options(), service_name) {
+        let Ok(TypeInner::Service(serv)) = env.find_type(&id.as_str().into()).map(|ty| ty.as_ref())
+        else {
+            continue;
+        };
+        let actor_type = converter.original_named_type(&id);
+        let class_decl = create_actor_class(
+            env,
+            &id,
+            &class_name,
+            serv,
+            actor_type,
+            &error_class,
+            converter,
+        );
+        module
+            .body
+            .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                span: DUMMY_SP,
+                decl: Decl::Class(class_decl),
+            })));
+    }
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map_or(String::new(), |c| c.to_uppercase().chain(chars).collect())
+}
+
+// The type of the actor service in the declarations
+fn service_type() -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::Ident(Ident::new(
+            "_SERVICE".into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )),
+        type_params: None,
+    })
+}
+
 fn create_actor_class(
     env: &TypeEnv,
     service_name: &str,
     capitalized_service_name: &str,
     serv: &[(String, Type)],
+    actor_type: TsType,
+    error_class: &str,
     converter: &mut TypeConverter,
 ) -> ClassDecl {
+    // The oneway methods of the actor resolve to their receipt
+    let actor_type = oneway_actor_type(actor_type, &oneway_methods(env, converter.options(), serv));
+
     // Create constructor
     let constructor = ClassMember::Constructor(Constructor {
         span: DUMMY_SP,
@@ -246,15 +419,7 @@ fn create_actor_class(
                         )),
                         type_params: Some(Box::new(TsTypeParamInstantiation {
                             span: DUMMY_SP,
-                            params: vec![Box::new(TsType::TsTypeRef(TsTypeRef {
-                                span: DUMMY_SP,
-                                type_name: TsEntityName::Ident(Ident::new(
-                                    "_SERVICE".into(),
-                                    DUMMY_SP,
-                                    SyntaxContext::empty(),
-                                )),
-                                type_params: None,
-                            }))],
+                            params: vec![Box::new(actor_type)],
                         })),
                     })),
                 })),
@@ -291,8 +456,35 @@ fn create_actor_class(
         .collect();
 
     // Combine all members
-    let mut class_body_members = vec![constructor];
+    let mut class_body_members: Vec<ClassMember> =
+        method_modes_table(env, converter.options(), serv)
+            .into_iter()
+            .collect();
+    class_body_members.push(constructor);
+    if converter.options().certified_queries {
+        class_body_members.push(certified_getter(capitalized_service_name));
+    }
+    if converter.options().call_options {
+        class_body_members.push(with_options_method(capitalized_service_name));
+    }
+    if converter.options().query_cache {
+        class_body_members.push(invalidate_method());
+    }
     class_body_members.extend(methods);
+    for (method_id, method_ty) in serv {
+        if let Some(func) = method_function(env, method_ty)
+            && let Some(method) = or_throw_method(
+                &mut converter.top_level_nodes(),
+                env,
+                service_name,
+                error_class,
+                method_id,
+                func,
+            )
+        {
+            class_body_members.push(method);
+        }
+    }
 
     ClassDecl {
         ident: get_ident_guarded(capitalized_service_name),
@@ -322,56 +514,75 @@ fn create_actor_method(
     converter: &mut TypeConverter,
 ) -> ClassMember {
     // Create parameters
-    let params = func
-        .args
-        .iter()
-        .enumerate()
-        .map(|(i, arg_ty)| {
-            let var_name = format!("arg{}", i);
-            Param {
+    let arg_names = argument_names(converter.options(), &func.args);
+    let params = if uses_named_args(converter.options(), &func.args) {
+        // Destructure the named-arguments object, so the body can refer to the arguments by name
+        let type_ann = create_named_args_type(
+            &mut converter.top_level_nodes(),
+            env,
+            &func.args,
+            &arg_names,
+        );
+        vec![Param {
+            span: DUMMY_SP,
+            decorators: vec![],
+            pat: Pat::Object(ObjectPat {
+                span: DUMMY_SP,
+                props: arg_names
+                    .iter()
+                    .map(|var_name| {
+                        ObjectPatProp::Assign(AssignPatProp {
+                            span: DUMMY_SP,
+                            key: BindingIdent {
+                                id: Ident::new(
+                                    var_name.as_str().into(),
+                                    DUMMY_SP,
+                                    SyntaxContext::empty(),
+                                ),
+                                type_ann: None,
+                            },
+                            value: None,
+                        })
+                    })
+                    .collect(),
+                optional: false,
+                type_ann: Some(Box::new(TsTypeAnn {
+                    span: DUMMY_SP,
+                    type_ann: Box::new(type_ann),
+                })),
+            }),
+        }]
+    } else {
+        func.args
+            .iter()
+            .zip(&arg_names)
+            .map(|(arg_ty, var_name)| Param {
                 span: DUMMY_SP,
                 decorators: vec![],
                 pat: Pat::Ident(BindingIdent {
-                    id: Ident::new(var_name.into(), DUMMY_SP, SyntaxContext::empty()),
+                    id: Ident::new(var_name.as_str().into(), DUMMY_SP, SyntaxContext::empty()),
                     type_ann: Some(Box::new(TsTypeAnn {
                         span: DUMMY_SP,
-                        type_ann: Box::new(convert_type_with_converter(
+                        type_ann: Box::new(convert_input_type_with_converter(
                             converter,
                             env,
                             &arg_ty.typ,
-                            None,
-                            true,
                         )),
                     })),
                 }),
-            }
-        })
-        .collect();
+            })
+            .collect()
+    };
 
     // Create return type
     let return_type = match func.rets.len() {
+        _ if is_oneway_submission(converter.options(), func) => oneway_receipt_type(),
         0 => TsType::TsKeywordType(TsKeywordType {
             span: DUMMY_SP,
             kind: TsKeywordTypeKind::TsVoidKeyword,
         }),
         1 => convert_type_with_converter(converter, env, &func.rets[0].typ, None, true),
-        _ => {
-            // Create a tuple type for multiple return values
-            TsType::TsTupleType(TsTupleType {
-                span: DUMMY_SP,
-                elem_types: func
-                    .rets
-                    .iter()
-                    .map(|ret| TsTupleElement {
-                        span: DUMMY_SP,
-                        label: None,
-                        ty: Box::new(convert_type_with_converter(
-                            converter, env, &ret.typ, None, true,
-                        )),
-                    })
-                    .collect(),
-            })
-        }
+        _ => create_multi_return_type(&mut converter.top_level_nodes(), env, &func.rets),
     };
 
     // Wrap return type in Promise
@@ -388,75 +599,112 @@ fn create_actor_method(
         })),
     };
 
-    // Generate converted arguments for the function call
-    let converted_args = func
-        .args
-        .iter()
-        .enumerate()
-        .map(|(i, arg_ty)| {
-            let var_name = format!("arg{}", i);
-
-            let arg_ident = Ident::new(var_name.into(), DUMMY_SP, SyntaxContext::empty());
-            let arg_expr = Expr::Ident(arg_ident);
-
-            // Apply type conversion
-            let converted_expr = converter.convert_to_candid(&arg_expr, &arg_ty.typ);
-
-            ExprOrSpread {
-                spread: None,
-                expr: Box::new(converted_expr),
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let actor_call = if contains_unicode_characters(method_id) {
-        Expr::Call(CallExpr {
+    let actor = Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(Expr::This(ThisExpr { span: DUMMY_SP })),
+        prop: MemberProp::Ident(IdentName {
+            span: DUMMY_SP,
+            sym: "actor".into(),
+        }),
+    });
+    let prop = if contains_unicode_characters(method_id) {
+        MemberProp::Computed(ComputedPropName {
             span: DUMMY_SP,
-            callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+            expr: Box::new(Expr::Lit(Lit::Str(Str {
                 span: DUMMY_SP,
-                obj: Box::new(Expr::Member(MemberExpr {
-                    span: DUMMY_SP,
-                    obj: Box::new(Expr::This(ThisExpr { span: DUMMY_SP })),
-                    prop: MemberProp::Ident(IdentName {
-                        span: DUMMY_SP,
-                        sym: "actor".into(),
-                    }),
-                })),
-                prop: MemberProp::Computed(ComputedPropName {
-                    span: DUMMY_SP,
-                    expr: Box::new(Expr::Lit(Lit::Str(Str {
-                        span: DUMMY_SP,
-                        value: method_id.into(),
-                        raw: None,
-                    }))),
-                }),
+                value: method_id.into(),
+                raw: None,
             }))),
-            args: converted_args,
-            type_args: None,
-            ctxt: SyntaxContext::empty(),
         })
     } else {
-        // Create the function call to the actor method
-        Expr::Call(CallExpr {
+        MemberProp::Ident(get_ident_guarded_keyword_ok(method_id).into())
+    };
+    let actor_method = Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(actor),
+        prop,
+    });
+    let method = Expr::Lit(Lit::Str(Str {
+        span: DUMMY_SP,
+        value: method_id.into(),
+        raw: None,
+    }));
+    let call_and_convert_stmts = if is_oneway_submission(converter.options(), func) {
+        let actor_call = Expr::Call(CallExpr {
             span: DUMMY_SP,
-            callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
-                span: DUMMY_SP,
-                obj: Box::new(Expr::Member(MemberExpr {
-                    span: DUMMY_SP,
-                    obj: Box::new(Expr::This(ThisExpr { span: DUMMY_SP })),
-                    prop: MemberProp::Ident(IdentName {
-                        span: DUMMY_SP,
-                        sym: "actor".into(),
-                    }),
-                })),
-                prop: MemberProp::Ident(get_ident_guarded_keyword_ok(method_id).into()),
-            }))),
-            args: converted_args,
+            callee: Callee::Expr(Box::new(actor_method)),
+            args: convert_args(func, &arg_names, converter),
             type_args: None,
             ctxt: SyntaxContext::empty(),
-        })
+        });
+        create_submit_stmts(with_canister_errors(
+            converter.options(),
+            method,
+            actor_call,
+        ))
+    } else {
+        create_call_stmts(func, &arg_names, actor_method, method, converter)
     };
 
+    ClassMember::Method(ClassMethod {
+        span: DUMMY_SP,
+        key: PropName::Ident(
+            get_ident_guarded(&member_name(converter.options(), method_id)).into(),
+        ),
+        function: Box::new(swc_core::ecma::ast::Function {
+            params,
+            decorators: vec![],
+            span: DUMMY_SP,
+            body: Some(BlockStmt {
+                span: DUMMY_SP,
+                stmts: call_and_convert_stmts,
+                ctxt: SyntaxContext::empty(),
+            }),
+            is_generator: false,
+            is_async: true,
+            type_params: None,
+            return_type: Some(Box::new(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::new(TsType::TsTypeRef(promise_return_type)),
+            })),
+            ctxt: SyntaxContext::empty(),
+        }),
+        kind: MethodKind::Method,
+        is_static: false,
+        accessibility: None,
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+    })
+}
+
+/// Creates the statements that call `callee` with the converted arguments and return the converted result:
+///
+/// ```ts
+/// const result = await callee(to_candid_arg0(arg0), ...);
+/// return from_candid_result(result);
+/// ```
+///
+/// `method` is the name of the called method, e.g. for the errors of the call.
+pub(super) fn create_call_stmts(
+    func: &Function,
+    arg_names: &[String],
+    callee: Expr,
+    method: Expr,
+    converter: &mut TypeConverter,
+) -> Vec<Stmt> {
+    let converted_args = convert_args(func, arg_names, converter);
+
+    // Create the function call to the actor method
+    let actor_call = Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(callee)),
+        args: converted_args,
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    });
+    let actor_call = with_canister_errors(converter.options(), method, actor_call);
+
     // Create await expression to call the actor
     let await_expr = Expr::Await(AwaitExpr {
         span: DUMMY_SP,
@@ -467,7 +715,7 @@ fn create_actor_method(
     let result_var = Ident::new("result".into(), DUMMY_SP, SyntaxContext::empty());
 
     // Create the common call and conversion logic
-    let call_and_convert_stmts = vec![
+    vec![
         // const result = await actor.method(args);
         Stmt::Decl(Decl::Var(Box::new(VarDecl {
             span: DUMMY_SP,
@@ -496,41 +744,45 @@ fn create_actor_method(
                 convert_multi_return_from_candid(converter, &result_expr, &func.rets)
             })),
         }),
-    ];
+    ]
+}
 
-    ClassMember::Method(ClassMethod {
-        span: DUMMY_SP,
-        key: PropName::Ident(get_ident_guarded(method_id).into()),
-        function: Box::new(swc_core::ecma::ast::Function {
-            params,
-            decorators: vec![],
-            span: DUMMY_SP,
-            body: Some(BlockStmt {
-                span: DUMMY_SP,
-                stmts: call_and_convert_stmts,
-                ctxt: SyntaxContext::empty(),
-            }),
-            is_generator: false,
-            is_async: true,
-            type_params: None,
-            return_type: Some(Box::new(TsTypeAnn {
-                span: DUMMY_SP,
-                type_ann: Box::new(TsType::TsTypeRef(promise_return_type)),
-            })),
-            ctxt: SyntaxContext::empty(),
-        }),
-        kind: MethodKind::Method,
-        is_static: false,
-        accessibility: None,
-        is_abstract: false,
-        is_optional: false,
-        is_override: false,
-    })
+/// Converts the arguments of a call to their Candid representation.
+fn convert_args(
+    func: &Function,
+    arg_names: &[String],
+    converter: &mut TypeConverter,
+) -> Vec<ExprOrSpread> {
+    func.args
+        .iter()
+        .zip(arg_names)
+        .map(|(arg_ty, var_name)| {
+            let arg_ident = Ident::new(var_name.as_str().into(), DUMMY_SP, SyntaxContext::empty());
+            let arg_expr = Expr::Ident(arg_ident);
+
+            // Apply type conversion
+            let converted_expr = converter.convert_to_candid(&arg_expr, &arg_ty.typ);
+
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(converted_expr),
+            }
+        })
+        .collect()
 }
 
-fn add_create_actor_exports(module: &mut Module, service_name: &str) {
+fn add_create_actor_exports(
+    module: &mut Module,
+    env: &TypeEnv,
+    prog: &IDLMergedProg,
+    service_name: &str,
+    idempotent: &[String],
+    oneway: &[String],
+    options: &GenerateActorOptions,
+) {
     // CreateActorOptions interface
-    let create_actor_options_interface = super::preamble::actor::create_actor_options_interface();
+    let create_actor_options_interface =
+        super::preamble::actor::create_actor_options_interface(options);
 
     module
         .body
@@ -540,7 +792,19 @@ fn add_create_actor_exports(module: &mut Module, service_name: &str) {
         })));
 
     // createActor function
-    let create_actor_function = create_actor_function(service_name);
+    let create_actor_function = create_actor_function(
+        "createActor",
+        &capitalize(service_name),
+        Expr::Ident(Ident::new(
+            "idlFactory".into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )),
+        service_type(),
+        idempotent,
+        oneway,
+        options,
+    );
 
     module
         .body
@@ -548,19 +812,68 @@ fn add_create_actor_exports(module: &mut Module, service_name: &str) {
             span: DUMMY_SP,
             decl: Decl::Fn(create_actor_function),
         })));
+
+    // create<Name>Actor functions of the named services, with their IDL factory, e.g. `idlFactories.broker`
+    for (id, class_name) in named_service_classes(env, options, service_name) {
+        let idl_factory = Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::new(Expr::Ident(Ident::new(
+                IDL_FACTORIES.into(),
+                DUMMY_SP,
+                SyntaxContext::empty(),
+            ))),
+            prop: MemberProp::Ident(IdentName::new(id.as_str().into(), DUMMY_SP)),
+        });
+        let actor_type = TsType::TsTypeRef(TsTypeRef {
+            span: DUMMY_SP,
+            type_name: TsEntityName::Ident(Ident::new(
+                format!("_{}", id).into(),
+                DUMMY_SP,
+                SyntaxContext::empty(),
+            )),
+            type_params: None,
+        });
+        // The methods annotated with `@idempotent` in the definition of the named service
+        let syntax = prog.lookup(id.as_str()).map(|s| &s.typ);
+        let idempotent = env.find_type(&id.as_str().into()).map_or_else(
+            |_| vec![],
+            |ty| idempotent_methods(env, options, ty, syntax),
+        );
+        let oneway = match env.find_type(&id.as_str().into()).map(|ty| ty.as_ref()) {
+            Ok(TypeInner::Service(serv)) => oneway_methods(env, options, serv),
+            _ => vec![],
+        };
+        let create_actor_function = create_actor_function(
+            &create_actor_function_name(&class_name),
+            &class_name,
+            idl_factory,
+            actor_type,
+            &idempotent,
+            &oneway,
+            options,
+        );
+        module
+            .body
+            .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                span: DUMMY_SP,
+                decl: Decl::Fn(create_actor_function),
+            })));
+    }
 }
 
-fn create_actor_function(service_name: &str) -> FnDecl {
+fn create_actor_function(
+    function_name: &str,
+    capitalized_service_name: &str,
+    idl_factory: Expr,
+    actor_type: TsType,
+    idempotent: &[String],
+    oneway: &[String],
+    options: &GenerateActorOptions,
+) -> FnDecl {
     let span = DUMMY_SP;
 
-    let capitalized_service_name = service_name
-        .chars()
-        .next()
-        .map_or(String::new(), |c| c.to_uppercase().collect::<String>())
-        + &service_name[1..];
-
     FnDecl {
-        ident: Ident::new("createActor".into(), span, SyntaxContext::empty()),
+        ident: Ident::new(function_name.into(), span, SyntaxContext::empty()),
         declare: false,
         function: Box::new(swc_core::ecma::ast::Function {
             params: vec![
@@ -611,7 +924,14 @@ fn create_actor_function(service_name: &str) -> FnDecl {
             span,
             body: Some(BlockStmt {
                 span,
-                stmts: create_actor_function_body(&capitalized_service_name),
+                stmts: create_actor_function_body(
+                    capitalized_service_name,
+                    idl_factory,
+                    actor_type,
+                    idempotent,
+                    oneway,
+                    options,
+                ),
                 ctxt: SyntaxContext::empty(),
             }),
             is_generator: false,
@@ -634,10 +954,17 @@ fn create_actor_function(service_name: &str) -> FnDecl {
     }
 }
 
-fn create_actor_function_body(capitalized_service_name: &str) -> Vec<Stmt> {
+fn create_actor_function_body(
+    capitalized_service_name: &str,
+    idl_factory: Expr,
+    actor_type: TsType,
+    idempotent: &[String],
+    oneway: &[String],
+    options: &GenerateActorOptions,
+) -> Vec<Stmt> {
     let span = DUMMY_SP;
 
-    vec![
+    let mut stmts = vec![
         // const agent = options.agent || HttpAgent.createSync({ ...options.agentOptions });
         Stmt::Decl(Decl::Var(Box::new(VarDecl {
             span,
@@ -773,120 +1100,156 @@ fn create_actor_function_body(capitalized_service_name: &str) -> Vec<Stmt> {
             alt: None,
         }),
         // const actor = Actor.createActor<_SERVICE>(idlFactory, { agent, canisterId, ...options.actorOptions });
-        Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        actor_decl("actor", create_actor_call(idl_factory.clone(), actor_type.clone())),
+    ];
+
+    // The certified actor is created with the same options, and wrapped like the actor
+    let mut actor = wrap_actor(options, "actor", idempotent, oneway);
+    if options.certified_queries {
+        // const certifiedActor = Actor.createActor<_SERVICE>(certified_idl_factory(idlFactory), { ... });
+        stmts.push(actor_decl(
+            "certifiedActor",
+            create_actor_call(certified_idl_factory(idl_factory), actor_type),
+        ));
+        actor = with_certified_actor(
+            actor,
+            wrap_actor(options, "certifiedActor", idempotent, oneway),
+        );
+    }
+
+    // return new Service(actor);
+    stmts.push(Stmt::Return(ReturnStmt {
+        span,
+        arg: Some(Box::new(Expr::New(NewExpr {
             span,
-            kind: VarDeclKind::Const,
-            declare: false,
-            decls: vec![VarDeclarator {
+            callee: Box::new(Expr::Ident(Ident::new(
+                capitalized_service_name.into(),
                 span,
-                name: Pat::Ident(BindingIdent {
-                    id: Ident::new("actor".into(), span, SyntaxContext::empty()),
-                    type_ann: None,
-                }),
-                init: Some(Box::new(Expr::Call(CallExpr {
+                SyntaxContext::empty(),
+            ))),
+            args: Some(vec![ExprOrSpread {
+                spread: None,
+                expr: Box::new(actor),
+            }]),
+            type_args: None,
+            ctxt: SyntaxContext::empty(),
+        }))),
+    }));
+    stmts
+}
+
+/// Returns a declaration of a constant holding an actor:
+///
+/// ```ts
+/// const actor = Actor.createActor<_SERVICE>(idlFactory, { ... });
+/// ```
+fn actor_decl(name: &str, actor: Expr) -> Stmt {
+    Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        span: DUMMY_SP,
+        kind: VarDeclKind::Const,
+        declare: false,
+        decls: vec![VarDeclarator {
+            span: DUMMY_SP,
+            name: Pat::Ident(BindingIdent {
+                id: Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty()),
+                type_ann: None,
+            }),
+            init: Some(Box::new(actor)),
+            definite: false,
+        }],
+        ctxt: SyntaxContext::empty(),
+    })))
+}
+
+/// Returns the creation of an actor with the agent, the canister id and the actor options of `createActor`:
+///
+/// ```ts
+/// Actor.createActor<_SERVICE>(idlFactory, { agent, canisterId, ...options.actorOptions })
+/// ```
+fn create_actor_call(idl_factory: Expr, actor_type: TsType) -> Expr {
+    let span = DUMMY_SP;
+
+    Expr::Call(CallExpr {
+        span,
+        callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+            span,
+            obj: Box::new(Expr::Ident(Ident::new(
+                "Actor".into(),
+                span,
+                SyntaxContext::empty(),
+            ))),
+            prop: MemberProp::Ident(IdentName {
+                span,
+                sym: "createActor".into(),
+            }),
+        }))),
+        args: vec![
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(idl_factory),
+            },
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(Expr::Object(ObjectLit {
                     span,
-                    callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
-                        span,
-                        obj: Box::new(Expr::Ident(Ident::new(
-                            "Actor".into(),
+                    props: vec![
+                        PropOrSpread::Prop(Box::new(Prop::Shorthand(Ident::new(
+                            "agent".into(),
                             span,
                             SyntaxContext::empty(),
-                        ))),
-                        prop: MemberProp::Ident(IdentName {
-                            span,
-                            sym: "createActor".into(),
-                        }),
-                    }))),
-                    args: vec![
-                        ExprOrSpread {
-                            spread: None,
-                            expr: Box::new(Expr::Ident(Ident::new(
-                                "idlFactory".into(),
+                        )))),
+                        PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                            key: PropName::Ident(IdentName {
+                                span,
+                                sym: "canisterId".into(),
+                            }),
+                            value: Box::new(Expr::Ident(Ident::new(
+                                "canisterId".into(),
                                 span,
                                 SyntaxContext::empty(),
                             ))),
-                        },
-                        ExprOrSpread {
-                            spread: None,
-                            expr: Box::new(Expr::Object(ObjectLit {
+                        }))),
+                        PropOrSpread::Spread(SpreadElement {
+                            dot3_token: span,
+                            expr: Box::new(Expr::Member(MemberExpr {
                                 span,
-                                props: vec![
-                                    PropOrSpread::Prop(Box::new(Prop::Shorthand(Ident::new(
-                                        "agent".into(),
-                                        span,
-                                        SyntaxContext::empty(),
-                                    )))),
-                                    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
-                                        key: PropName::Ident(IdentName {
-                                            span,
-                                            sym: "canisterId".into(),
-                                        }),
-                                        value: Box::new(Expr::Ident(Ident::new(
-                                            "canisterId".into(),
-                                            span,
-                                            SyntaxContext::empty(),
-                                        ))),
-                                    }))),
-                                    PropOrSpread::Spread(SpreadElement {
-                                        dot3_token: span,
-                                        expr: Box::new(Expr::Member(MemberExpr {
-                                            span,
-                                            obj: Box::new(Expr::Ident(Ident::new(
-                                                "options".into(),
-                                                span,
-                                                SyntaxContext::empty(),
-                                            ))),
-                                            prop: MemberProp::Ident(IdentName {
-                                                span,
-                                                sym: "actorOptions".into(),
-                                            }),
-                                        })),
-                                    }),
-                                ],
+                                obj: Box::new(Expr::Ident(Ident::new(
+                                    "options".into(),
+                                    span,
+                                    SyntaxContext::empty(),
+                                ))),
+                                prop: MemberProp::Ident(IdentName {
+                                    span,
+                                    sym: "actorOptions".into(),
+                                }),
                             })),
-                        },
+                        }),
                     ],
-                    type_args: Some(Box::new(TsTypeParamInstantiation {
-                        span,
-                        params: vec![Box::new(TsType::TsTypeRef(TsTypeRef {
-                            span,
-                            type_name: TsEntityName::Ident(Ident::new(
-                                "_SERVICE".into(),
-                                span,
-                                SyntaxContext::empty(),
-                            )),
-                            type_params: None,
-                        }))],
-                    })),
-                    ctxt: SyntaxContext::empty(),
-                }))),
-                definite: false,
-            }],
-            ctxt: SyntaxContext::empty(),
-        }))),
-        // return new Service(actor);
-        Stmt::Return(ReturnStmt {
+                })),
+            },
+        ],
+        type_args: Some(Box::new(TsTypeParamInstantiation {
             span,
-            arg: Some(Box::new(Expr::New(NewExpr {
-                span,
-                callee: Box::new(Expr::Ident(Ident::new(
-                    capitalized_service_name.into(),
-                    span,
-                    SyntaxContext::empty(),
-                ))),
-                args: Some(vec![
-                    ExprOrSpread {
-                        spread: None,
-                        expr: Box::new(Expr::Ident(Ident::new(
-                            "actor".into(),
-                            span,
-                            SyntaxContext::empty(),
-                        ))),
-                    },
-                ]),
-                type_args: None,
-                ctxt: SyntaxContext::empty(),
-            }))),
-        }),
-    ]
+            params: vec![Box::new(actor_type)],
+        })),
+        ctxt: SyntaxContext::empty(),
+    })
+}
+
+/// Wraps the actor `name` with the interceptors, the query cache, the retry policy and the oneway receipts, if enabled.
+fn wrap_actor(
+    options: &GenerateActorOptions,
+    name: &str,
+    idempotent: &[String],
+    oneway: &[String],
+) -> Expr {
+    let actor = Expr::Ident(Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty()));
+    // The interceptors see a single call for all its attempts, cached or not
+    with_interceptors(
+        options,
+        with_query_cache(
+            options,
+            with_retry_policy(options, with_oneway_receipts(oneway, actor), idempotent),
+        ),
+    )
 }
@@ -1,17 +1,29 @@
+use super::branded;
 use super::comments::PosCursor;
-use super::new_typescript_native_types::{convert_type_with_converter, is_recursive_optional};
+use super::naming;
+use super::new_typescript_native_types::{
+    contains_mutable_value, convert_type_with_converter, is_lenient_input, is_recursive_optional,
+    map_entry_types, return_value_names,
+};
 use super::original_typescript_types::OriginalTypescriptTypes;
-use super::utils::{EnumDeclarations, contains_unicode_characters, get_ident_guarded};
+use super::preamble::input::{BIGINT_INPUT, input_of};
+use super::preamble::service_ref;
+use super::utils::{
+    EnumDeclarations, contains_unicode_characters, get_ident_guarded, get_ident_guarded_keyword_ok,
+};
 use candid::types::{ArgType, Field, Label, Type, TypeEnv, TypeInner};
 use std::collections::{HashMap, HashSet};
 use swc_core::common::{DUMMY_SP, SyntaxContext, comments::SingleThreadedComments};
 use swc_core::ecma::ast::*;
+
+use crate::GenerateActorOptions;
 // Type aliases to simplify complex types used throughout this module
 
 pub type TopLevelNodes<'a> = (
     &'a mut EnumDeclarations,
     &'a mut SingleThreadedComments,
     &'a mut PosCursor,
+    &'a GenerateActorOptions,
 );
 /// Provides functions to generate TypeScript expressions that convert
 /// between new TypeScript Native and original TypeScript (current agent-js) representations by generating conversion functions.
@@ -36,12 +48,13 @@ pub struct TypeConverter<'a> {
     // For adding comments to the generated functions
     comments: &'a mut SingleThreadedComments,
     cursor: &'a mut PosCursor,
+    options: &'a GenerateActorOptions,
 }
 
 impl<'a> TypeConverter<'a> {
     /// Create a new TypeConverter with the given type environment
     pub fn new(env: &'a TypeEnv, top_level_nodes: &'a mut TopLevelNodes<'a>) -> Self {
-        let (enum_declarations, comments, cursor) = top_level_nodes;
+        let (enum_declarations, comments, cursor, options) = top_level_nodes;
         TypeConverter {
             env,
             to_candid_functions: HashMap::new(),
@@ -57,6 +70,7 @@ impl<'a> TypeConverter<'a> {
             enum_declarations,
             comments,
             cursor,
+            options: *options,
         }
     }
 
@@ -65,11 +79,16 @@ impl<'a> TypeConverter<'a> {
         self.generated_functions.values().cloned().collect()
     }
 
+    pub fn options(&self) -> &'a GenerateActorOptions {
+        self.options
+    }
+
     pub fn top_level_nodes(&mut self) -> TopLevelNodes<'_> {
         (
             &mut self.enum_declarations,
             &mut self.comments,
             &mut self.cursor,
+            self.options,
         )
     }
 
@@ -103,23 +122,27 @@ impl<'a> TypeConverter<'a> {
             TypeInner::Int => false,
             TypeInner::Nat64 => false,
             TypeInner::Int64 => false,
-            TypeInner::Principal => false,
+            // Principals represented as text are parsed and printed
+            TypeInner::Principal => self.options.principal_text,
             TypeInner::Empty => false,
             TypeInner::Func(_) => false,
-            TypeInner::Service(_) => false,
+            // Service references are branded with the interface of the service
+            TypeInner::Service(_) => self.options.principal_text || self.options.service_refs,
             // Types that always need conversion
             TypeInner::Opt(_) => true,
             TypeInner::Variant(_) => true,
             // Container types - need conversion only if their contents need conversion
-            TypeInner::Vec(inner) => self.needs_conversion(inner),
+            TypeInner::Vec(inner) => self.is_map(ty, None) || self.needs_conversion(inner),
             TypeInner::Record(fields) => {
                 // Only needs conversion if any field needs conversion
                 fields.iter().any(|field| self.needs_conversion(&field.ty))
             }
+            TypeInner::Var(id) if self.options.type_override(id).is_some() => true,
+            TypeInner::Var(id) if branded::is_branded(self.env, self.options, id) => true,
             TypeInner::Var(id) => {
                 // Check if the named type needs conversion
                 if let Ok(actual_ty) = self.env.rec_find_type(id) {
-                    self.needs_conversion(actual_ty)
+                    self.is_map(actual_ty, Some(id)) || self.needs_conversion(actual_ty)
                 } else {
                     true // Conservative default
                 }
@@ -130,6 +153,45 @@ impl<'a> TypeConverter<'a> {
         result
     }
 
+    /// Check if a type requires conversion to Candid, which includes normalizing lenient inputs
+    fn needs_to_candid_conversion(&mut self, ty: &Type) -> bool {
+        self.needs_conversion(ty)
+            || is_lenient_input(self.env, self.options, ty, false, &mut HashSet::new())
+            || (self.options.readonly_types
+                && contains_mutable_value(self.env, self.options, ty, false, &mut HashSet::new()))
+    }
+
+    /// Check if a type requires conversion from Candid, which includes freezing the results
+    fn needs_from_candid_conversion(&mut self, ty: &Type) -> bool {
+        self.needs_conversion(ty)
+            || (self.options.freeze_results
+                && contains_mutable_value(self.env, self.options, ty, true, &mut HashSet::new()))
+    }
+
+    /// Check if the values converted from Candid to a type are frozen: records, tuples,
+    /// variant objects and arrays, when freezing the results is enabled
+    fn is_frozen(&self, ty: &Type) -> bool {
+        if !self.options.freeze_results {
+            return false;
+        }
+        match ty.as_ref() {
+            TypeInner::Record(_) => true,
+            TypeInner::Variant(fields) => !fields
+                .iter()
+                .all(|f| matches!(f.ty.as_ref(), TypeInner::Null)),
+            TypeInner::Vec(_) => {
+                !self.is_map(ty, None)
+                    && contains_mutable_value(self.env, self.options, ty, true, &mut HashSet::new())
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if a type is an association list represented as a `Map`
+    fn is_map(&self, ty: &Type, name: Option<&str>) -> bool {
+        map_entry_types(self.env, self.options, ty, name).is_some()
+    }
+
     fn type_prefix(&self, ty: &Type) -> &str {
         match ty.as_ref() {
             TypeInner::Null => "null",
@@ -243,6 +305,15 @@ impl<'a> TypeConverter<'a> {
         match ty.as_ref() {
             TypeInner::Null => self.create_ident(param_name),
             TypeInner::Bool => self.create_ident(param_name),
+            TypeInner::Nat | TypeInner::Int | TypeInner::Nat64 | TypeInner::Int64
+                if self.options.input_types =>
+            {
+                // Lenient inputs accept safe integers for bigints: bigint_input(value)
+                self.create_call(
+                    BIGINT_INPUT,
+                    vec![self.create_arg(self.create_ident(param_name))],
+                )
+            }
             TypeInner::Nat
             | TypeInner::Int
             | TypeInner::Nat64
@@ -263,16 +334,35 @@ impl<'a> TypeConverter<'a> {
             }
             TypeInner::Principal => self.convert_principal_to_candid_body(param_name),
             TypeInner::Opt(inner) => self.convert_opt_to_candid_body(inner, param_name),
+            TypeInner::Vec(inner) if self.is_map(ty, None) => {
+                self.convert_map_to_candid_body(inner, param_name)
+            }
             TypeInner::Vec(inner) => self.convert_vec_to_candid_body(inner, param_name),
             TypeInner::Record(fields) => self.convert_record_to_candid_body(fields, param_name),
             TypeInner::Variant(fields) => self.convert_variant_to_candid_body(fields, param_name),
             TypeInner::Func(func) => self.convert_func_to_candid_body(func, param_name),
-            TypeInner::Service(_) => self.create_ident(param_name), // Pass through as-is
+            // Services are represented as Principals
+            TypeInner::Service(_) => self.convert_principal_to_candid_body(param_name),
             TypeInner::Var(id) => {
+                // Overridden types are converted by the user-defined function
+                if let Some(type_override) = self.options.type_override(id) {
+                    return self.create_call(
+                        &type_override.to_candid,
+                        vec![self.create_arg(self.create_ident(param_name))],
+                    );
+                }
+
                 // For named types, delegate to another conversion function
                 if let Ok(actual_ty) = self.env.rec_find_type(id) {
+                    // Named association lists can be represented as a Map on their own
+                    if let TypeInner::Vec(inner) = actual_ty.as_ref()
+                        && self.is_map(actual_ty, Some(id))
+                    {
+                        return self.convert_map_to_candid_body(inner, param_name);
+                    }
+
                     // If the actual type doesn't need conversion, return the expression directly
-                    if !self.needs_conversion(actual_ty) {
+                    if !self.needs_to_candid_conversion(actual_ty) {
                         return self.create_ident(param_name);
                     }
 
@@ -298,8 +388,44 @@ impl<'a> TypeConverter<'a> {
     // --- Type-specific conversion methods (TypeScript Native -> Candid) ---
 
     fn convert_principal_to_candid_body(&mut self, param_name: &str) -> Expr {
+        // Principal text is parsed, which validates it at the boundary
+        if self.options.principal_text {
+            return self.create_call(
+                "Principal.fromText",
+                vec![self.create_arg(self.create_ident(param_name))],
+            );
+        }
         // Principal objects are already compatible
-        self.create_ident(param_name)
+        if !self.options.input_types {
+            return self.create_ident(param_name);
+        }
+        self.convert_principal_input(self.create_ident(param_name))
+    }
+
+    /// Lenient inputs accept the principal text:
+    /// typeof value === "string" ? Principal.fromText(value) : value
+    fn convert_principal_input(&self, value: Expr) -> Expr {
+        Expr::Cond(CondExpr {
+            span: DUMMY_SP,
+            test: Box::new(Expr::Bin(BinExpr {
+                span: DUMMY_SP,
+                op: BinaryOp::EqEqEq,
+                left: Box::new(Expr::Unary(UnaryExpr {
+                    span: DUMMY_SP,
+                    op: UnaryOp::TypeOf,
+                    arg: Box::new(value.clone()),
+                })),
+                right: Box::new(Expr::Lit(Lit::Str(Str {
+                    span: DUMMY_SP,
+                    value: "string".into(),
+                    raw: None,
+                }))),
+            })),
+            cons: Box::new(
+                self.create_call("Principal.fromText", vec![self.create_arg(value.clone())]),
+            ),
+            alt: Box::new(value),
+        })
     }
 
     fn convert_opt_to_candid_body(&mut self, inner: &Type, param_name: &str) -> Expr {
@@ -389,7 +515,7 @@ impl<'a> TypeConverter<'a> {
         }
 
         // For inner types that don't need conversion, we can simplify
-        if !self.needs_conversion(inner) {
+        if !self.needs_to_candid_conversion(inner) {
             return Expr::Cond(CondExpr {
                 span: DUMMY_SP,
                 test: Box::new(Expr::Bin(BinExpr {
@@ -447,7 +573,14 @@ impl<'a> TypeConverter<'a> {
             }
             _ => {
                 // Optimization for inner types that don't need conversion
-                if !self.needs_conversion(inner) {
+                if !self.needs_to_candid_conversion(inner) {
+                    // Readonly arrays are copied to the mutable arrays of the declarations
+                    if self.options.readonly_types {
+                        return self.create_call(
+                            "Array.from",
+                            vec![self.create_arg(self.create_ident(param_name))],
+                        );
+                    }
                     return self.create_ident(param_name);
                 }
 
@@ -491,6 +624,36 @@ impl<'a> TypeConverter<'a> {
         }
     }
 
+    fn convert_map_to_candid_body(&mut self, entry: &Type, param_name: &str) -> Expr {
+        // Array.from(value) already yields the [key, value] tuples
+        let mut args = vec![self.create_arg(self.create_ident(param_name))];
+
+        if self.needs_to_candid_conversion(entry) {
+            let entry_function_name = self.get_to_candid_function_name(entry);
+            self.generate_to_candid_function(entry, &entry_function_name);
+
+            // Array.from(value, x => to_candid_entry(x))
+            args.push(self.create_arg(Expr::Arrow(ArrowExpr {
+                span: DUMMY_SP,
+                params: vec![Pat::Ident(BindingIdent {
+                    id: Ident::new("x".into(), DUMMY_SP, SyntaxContext::empty()),
+                    type_ann: None,
+                })],
+                body: Box::new(BlockStmtOrExpr::Expr(Box::new(self.create_call(
+                    &entry_function_name,
+                    vec![self.create_arg(self.create_ident("x"))],
+                )))),
+                is_async: false,
+                is_generator: false,
+                type_params: None,
+                return_type: None,
+                ctxt: SyntaxContext::empty(),
+            })));
+        }
+
+        self.create_call("Array.from", args)
+    }
+
     fn convert_record_to_candid_body(&mut self, fields: &[Field], param_name: &str) -> Expr {
         // If the record is a tuple, handle differently
         if self.is_tuple(fields) {
@@ -507,13 +670,14 @@ impl<'a> TypeConverter<'a> {
                         Label::Named(name) => name.clone(),
                         Label::Id(n) | Label::Unnamed(n) => format!("_{}_", n),
                     };
+                    let ts_field_name = naming::field_name(self.options, &field.id);
 
                     // Get the field from the input object
                     let field_access = Expr::Member(MemberExpr {
                         span: DUMMY_SP,
                         obj: Box::new(self.create_ident(param_name)),
                         prop: MemberProp::Ident(
-                            Ident::new(field_name.clone().into(), DUMMY_SP, SyntaxContext::empty())
+                            Ident::new(ts_field_name.into(), DUMMY_SP, SyntaxContext::empty())
                                 .into(),
                         ),
                     });
@@ -525,38 +689,14 @@ impl<'a> TypeConverter<'a> {
                     // Convert the field value based on its type
                     let value = match field.ty.as_ref() {
                         TypeInner::Opt(inner) => {
-                            // For optional fields, handle undefined/null specially
-                            if !self.needs_conversion(inner) {
-                                Expr::Cond(CondExpr {
-                                    span: DUMMY_SP,
-                                    test: Box::new(field_access.clone()),
-                                    cons: Box::new(self.create_call(
-                                        "candid_some",
-                                        vec![self.create_arg(field_access.clone())],
-                                    )),
-                                    alt: Box::new(self.create_call("candid_none", vec![])),
-                                })
-                            } else {
-                                let inner_function_name = self.get_to_candid_function_name(inner);
-                                self.generate_to_candid_function(inner, &inner_function_name);
-
-                                Expr::Cond(CondExpr {
-                                    span: DUMMY_SP,
-                                    test: Box::new(field_access.clone()),
-                                    cons: Box::new(self.create_call(
-                                        "candid_some",
-                                        vec![self.create_arg(self.create_call(
-                                            &inner_function_name,
-                                            vec![self.create_arg(field_access.clone())],
-                                        ))],
-                                    )),
-                                    alt: Box::new(self.create_call("candid_none", vec![])),
-                                })
-                            }
+                            // Optional fields are optional properties: a missing property maps to `[]`.
+                            // For `opt opt T` fields, `null` is the inner none, so only `undefined` is absent.
+                            let undefined_only = matches!(inner.as_ref(), TypeInner::Opt(_));
+                            self.convert_opt_field_to_candid(inner, field_access, undefined_only)
                         }
                         _ => {
                             // For normal fields, check if conversion is needed
-                            if !self.needs_conversion(&field.ty) {
+                            if !self.needs_to_candid_conversion(&field.ty) {
                                 field_access
                             } else {
                                 // Convert the value using appropriate function
@@ -602,7 +742,7 @@ impl<'a> TypeConverter<'a> {
                     });
 
                     // Check if conversion is needed
-                    let value = if !self.needs_conversion(&field.ty) {
+                    let value = if !self.needs_to_candid_conversion(&field.ty) {
                         elem_access
                     } else {
                         // Convert the tuple element
@@ -746,38 +886,12 @@ impl<'a> TypeConverter<'a> {
 
                 let field_result = match field.ty.as_ref() {
                     TypeInner::Opt(inner) => {
-                        // For optional fields, handle undefined/null specially
-                        if !self.needs_conversion(inner) {
-                            Expr::Cond(CondExpr {
-                                span: DUMMY_SP,
-                                test: Box::new(field_access.clone()),
-                                cons: Box::new(self.create_call(
-                                    "candid_some",
-                                    vec![self.create_arg(field_access.clone())],
-                                )),
-                                alt: Box::new(self.create_call("candid_none", vec![])),
-                            })
-                        } else {
-                            let inner_function_name = self.get_to_candid_function_name(inner);
-                            self.generate_to_candid_function(inner, &inner_function_name);
-
-                            Expr::Cond(CondExpr {
-                                span: DUMMY_SP,
-                                test: Box::new(field_access.clone()),
-                                cons: Box::new(self.create_call(
-                                    "candid_some",
-                                    vec![self.create_arg(self.create_call(
-                                        &inner_function_name,
-                                        vec![self.create_arg(field_access.clone())],
-                                    ))],
-                                )),
-                                alt: Box::new(self.create_call("candid_none", vec![])),
-                            })
-                        }
+                        // Optional variant payloads are `T | null`
+                        self.convert_opt_field_to_candid(inner, field_access.clone(), false)
                     }
                     _ => {
                         // For normal fields, check if conversion is needed
-                        if !self.needs_conversion(&field.ty) {
+                        if !self.needs_to_candid_conversion(&field.ty) {
                             field_access
                         } else {
                             let inner_function_name = self.get_to_candid_function_name(&field.ty);
@@ -812,13 +926,90 @@ impl<'a> TypeConverter<'a> {
             result
         }
     }
+    /// Converts an optional record or variant field, mapping a missing value to `candid_none()`.
+    /// With `optional_fields`, compares against `null`/`undefined` explicitly,
+    /// so falsy values like `0`, `""` or `false` are kept.
+    fn convert_opt_field_to_candid(
+        &mut self,
+        inner: &Type,
+        field_access: Expr,
+        undefined_only: bool,
+    ) -> Expr {
+        let value = if !self.needs_to_candid_conversion(inner) {
+            field_access.clone()
+        } else {
+            let inner_function_name = self.get_to_candid_function_name(inner);
+            self.generate_to_candid_function(inner, &inner_function_name);
+            self.create_call(
+                &inner_function_name,
+                vec![self.create_arg(field_access.clone())],
+            )
+        };
+
+        if !self.options.optional_fields {
+            // value.field ? candid_some(value.field) : candid_none()
+            return Expr::Cond(CondExpr {
+                span: DUMMY_SP,
+                test: Box::new(field_access),
+                cons: Box::new(self.create_call("candid_some", vec![self.create_arg(value)])),
+                alt: Box::new(self.create_call("candid_none", vec![])),
+            });
+        }
+
+        // value.field == null ? candid_none() : candid_some(value.field)
+        let (op, absent) = if undefined_only {
+            (BinaryOp::EqEqEq, self.create_ident("undefined"))
+        } else {
+            (
+                BinaryOp::EqEq,
+                Expr::Lit(Lit::Null(Null { span: DUMMY_SP })),
+            )
+        };
+        Expr::Cond(CondExpr {
+            span: DUMMY_SP,
+            test: Box::new(Expr::Bin(BinExpr {
+                span: DUMMY_SP,
+                op,
+                left: Box::new(field_access),
+                right: Box::new(absent),
+            })),
+            cons: Box::new(self.create_call("candid_none", vec![])),
+            alt: Box::new(self.create_call("candid_some", vec![self.create_arg(value)])),
+        })
+    }
+
     fn convert_func_to_candid_body(
         &mut self,
         _func: &candid::types::Function,
         param_name: &str,
     ) -> Expr {
-        // Functions are represented as Principals
-        self.create_ident(param_name)
+        // Functions are represented as [Principal, string]
+        if !self.options.input_types {
+            return self.create_ident(param_name);
+        }
+
+        // [principal_input(value[0]), value[1]]
+        let elem = |i: usize| {
+            Expr::Member(MemberExpr {
+                span: DUMMY_SP,
+                obj: Box::new(self.create_ident(param_name)),
+                prop: MemberProp::Computed(ComputedPropName {
+                    span: DUMMY_SP,
+                    expr: Box::new(Expr::Lit(Lit::Num(Number {
+                        span: DUMMY_SP,
+                        value: i as f64,
+                        raw: None,
+                    }))),
+                }),
+            })
+        };
+        Expr::Array(ArrayLit {
+            span: DUMMY_SP,
+            elems: vec![
+                Some(self.create_arg(self.convert_principal_input(elem(0)))),
+                Some(self.create_arg(elem(1))),
+            ],
+        })
     }
 
     // --- Type-specific conversion methods (Candid -> TypeScript) ---
@@ -827,7 +1018,7 @@ impl<'a> TypeConverter<'a> {
     /// for the given Candid type and expression.
     pub fn convert_from_candid(&mut self, expr: &Expr, ty: &Type) -> Expr {
         // For simple types that don't need conversion, return the expression directly
-        if !self.needs_conversion(ty) {
+        if !self.needs_from_candid_conversion(ty) {
             return expr.clone();
         }
 
@@ -865,7 +1056,7 @@ impl<'a> TypeConverter<'a> {
             // For recursive types, return a placeholder initially
             self.create_ident(param_name)
         } else {
-            self.generate_from_candid_body(ty, param_name)
+            self.generate_frozen_from_candid_body(ty, param_name)
         };
 
         // Create function declaration with type annotations
@@ -914,7 +1105,7 @@ impl<'a> TypeConverter<'a> {
 
         // If recursive, update the function body now that the function exists
         if is_recursive {
-            let body_expr = self.generate_from_candid_body(ty, param_name);
+            let body_expr = self.generate_frozen_from_candid_body(ty, param_name);
             if let Some(Stmt::Decl(Decl::Fn(fn_decl))) =
                 self.generated_functions.get_mut(function_name)
                 && let Some(BlockStmt { stmts, .. }) = &mut fn_decl.function.body
@@ -933,6 +1124,16 @@ impl<'a> TypeConverter<'a> {
         convert_type_with_converter(self, self.env, ty, None, true)
     }
 
+    /// Generate the conversion from Candid, wrapped in `Object.freeze` if the values are frozen
+    fn generate_frozen_from_candid_body(&mut self, ty: &Type, param_name: &str) -> Expr {
+        let body_expr = self.generate_from_candid_body(ty, param_name);
+        if self.is_frozen(ty) {
+            self.create_call("Object.freeze", vec![self.create_arg(body_expr)])
+        } else {
+            body_expr
+        }
+    }
+
     fn generate_from_candid_body(&mut self, ty: &Type, param_name: &str) -> Expr {
         match ty.as_ref() {
             TypeInner::Null => self.create_ident(param_name),
@@ -956,16 +1157,54 @@ impl<'a> TypeConverter<'a> {
             }
             TypeInner::Principal => self.convert_principal_from_candid_body(param_name),
             TypeInner::Opt(inner) => self.convert_opt_from_candid_body(inner, param_name),
+            TypeInner::Vec(inner) if self.is_map(ty, None) => {
+                self.convert_map_from_candid_body(inner, param_name)
+            }
             TypeInner::Vec(inner) => self.convert_vec_from_candid_body(inner, param_name),
             TypeInner::Record(fields) => self.convert_record_from_candid_body(fields, param_name),
             TypeInner::Variant(fields) => self.convert_variant_from_candid_body(fields, param_name),
             TypeInner::Func(func) => self.convert_func_from_candid_body(func, param_name),
-            TypeInner::Service(_) => self.create_ident(param_name), // Pass through as-is
+            TypeInner::Service(_) if self.options.service_refs => {
+                self.convert_service_ref_from_candid_body(ty, param_name)
+            }
+            TypeInner::Service(_) => self.convert_principal_from_candid_body(param_name),
             TypeInner::Var(id) => {
+                // Overridden types are converted by the user-defined function
+                if let Some(type_override) = self.options.type_override(id) {
+                    return self.create_call(
+                        &type_override.from_candid,
+                        vec![self.create_arg(self.create_ident(param_name))],
+                    );
+                }
+
+                // Named services are referenced with their interface, e.g. `ServiceRef<brokerInterface>`
+                if self.options.service_refs
+                    && let Ok(actual_ty) = self.env.rec_find_type(id)
+                    && matches!(actual_ty.as_ref(), TypeInner::Service(_))
+                {
+                    return self.convert_service_ref_from_candid_body(ty, param_name);
+                }
+
+                // Branded types are branded values of their primitive type, converted first
+                // since principals represented as text are printed
+                if branded::is_branded(self.env, self.options, id)
+                    && let Ok(actual_ty) = self.env.rec_find_type(id)
+                {
+                    let value = self.generate_from_candid_body(actual_ty, param_name);
+                    return branded::brand_value(value, id);
+                }
+
                 // For named types, delegate to another conversion function
                 if let Ok(actual_ty) = self.env.rec_find_type(id) {
+                    // Named association lists can be represented as a Map on their own
+                    if let TypeInner::Vec(inner) = actual_ty.as_ref()
+                        && self.is_map(actual_ty, Some(id))
+                    {
+                        return self.convert_map_from_candid_body(inner, param_name);
+                    }
+
                     // If the actual type doesn't need conversion, return directly
-                    if !self.needs_conversion(actual_ty) {
+                    if !self.needs_from_candid_conversion(actual_ty) {
                         return self.create_ident(param_name);
                     }
 
@@ -994,10 +1233,21 @@ impl<'a> TypeConverter<'a> {
     }
 
     fn convert_principal_from_candid_body(&mut self, param_name: &str) -> Expr {
+        // Principals represented as text are printed: value.toText()
+        if self.options.principal_text {
+            return self.create_call(&format!("{}.toText", param_name), vec![]);
+        }
         // Principal objects are already compatible
         self.create_ident(param_name)
     }
 
+    fn convert_service_ref_from_candid_body(&mut self, ty: &Type, param_name: &str) -> Expr {
+        // Service references are branded principals: value as ServiceRef<T>
+        let value = self.convert_principal_from_candid_body(param_name);
+        let service_ref = self.create_ts_type_annotation(ty);
+        service_ref::service_ref_value(value, service_ref)
+    }
+
     fn convert_opt_from_candid_body(&mut self, inner: &Type, param_name: &str) -> Expr {
         // Check for recursive option types (Some<T> | None pattern)
         if let TypeInner::Var(id) = inner.as_ref()
@@ -1057,7 +1307,7 @@ impl<'a> TypeConverter<'a> {
         }
 
         // For inner types that don't need conversion, optimize
-        if !self.needs_conversion(inner) {
+        if !self.needs_from_candid_conversion(inner) {
             return Expr::Cond(CondExpr {
                 span: DUMMY_SP,
                 test: Box::new(Expr::Bin(BinExpr {
@@ -1196,7 +1446,7 @@ impl<'a> TypeConverter<'a> {
             }
             _ => {
                 // Optimization for inner types that don't need conversion
-                if !self.needs_conversion(inner) {
+                if !self.needs_from_candid_conversion(inner) {
                     return self.create_ident(param_name);
                 }
 
@@ -1240,6 +1490,19 @@ impl<'a> TypeConverter<'a> {
         }
     }
 
+    fn convert_map_from_candid_body(&mut self, entry: &Type, param_name: &str) -> Expr {
+        // new Map(value.map(x => from_candid_entry(x)))
+        let entries = self.convert_vec_from_candid_body(entry, param_name);
+
+        Expr::New(NewExpr {
+            span: DUMMY_SP,
+            callee: Box::new(self.create_ident("Map")),
+            args: Some(vec![self.create_arg(entries)]),
+            type_args: None,
+            ctxt: SyntaxContext::empty(),
+        })
+    }
+
     fn convert_record_from_candid_body(&mut self, fields: &[Field], param_name: &str) -> Expr {
         // If the record is a tuple, handle differently
         if self.is_tuple(fields) {
@@ -1256,6 +1519,7 @@ impl<'a> TypeConverter<'a> {
                         Label::Named(name) => name.clone(),
                         Label::Id(n) | Label::Unnamed(n) => format!("_{}_", n),
                     };
+                    let ts_field_name = naming::field_name(self.options, &field.id);
 
                     // Get the field from the input object
                     let field_access = Expr::Member(MemberExpr {
@@ -1268,12 +1532,12 @@ impl<'a> TypeConverter<'a> {
                     });
 
                     let prop_name = PropName::Ident(
-                        Ident::new(field_name.into(), DUMMY_SP, SyntaxContext::empty()).into(),
+                        Ident::new(ts_field_name.into(), DUMMY_SP, SyntaxContext::empty()).into(),
                     );
 
                     // Convert the field value based on its type
                     let value = match field.ty.as_ref() {
-                        TypeInner::Opt(_) => {
+                        TypeInner::Opt(_) if !self.options.optional_fields => {
                             // For optional fields, use a utility function
                             Expr::Call(CallExpr {
                                 span: DUMMY_SP,
@@ -1292,9 +1556,73 @@ impl<'a> TypeConverter<'a> {
                                 ctxt: SyntaxContext::empty(),
                             })
                         }
+                        TypeInner::Opt(inner) => {
+                            // Absent optional fields are omitted from the object:
+                            // ...(value.field.length === 0 ? {} : { field: value.field[0] })
+                            let is_empty = Expr::Bin(BinExpr {
+                                span: DUMMY_SP,
+                                op: BinaryOp::EqEqEq,
+                                left: Box::new(Expr::Member(MemberExpr {
+                                    span: DUMMY_SP,
+                                    obj: Box::new(field_access.clone()),
+                                    prop: MemberProp::Ident(
+                                        Ident::new(
+                                            "length".into(),
+                                            DUMMY_SP,
+                                            SyntaxContext::empty(),
+                                        )
+                                        .into(),
+                                    ),
+                                })),
+                                right: Box::new(Expr::Lit(Lit::Num(Number {
+                                    span: DUMMY_SP,
+                                    value: 0.0,
+                                    raw: None,
+                                }))),
+                            });
+                            let first_element = Expr::Member(MemberExpr {
+                                span: DUMMY_SP,
+                                obj: Box::new(field_access),
+                                prop: MemberProp::Computed(ComputedPropName {
+                                    span: DUMMY_SP,
+                                    expr: Box::new(Expr::Lit(Lit::Num(Number {
+                                        span: DUMMY_SP,
+                                        value: 0.0,
+                                        raw: None,
+                                    }))),
+                                }),
+                            });
+                            let present = Expr::Object(ObjectLit {
+                                span: DUMMY_SP,
+                                props: vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(
+                                    KeyValueProp {
+                                        key: prop_name,
+                                        value: Box::new(
+                                            self.convert_from_candid(&first_element, inner),
+                                        ),
+                                    },
+                                )))],
+                            });
+
+                            return PropOrSpread::Spread(SpreadElement {
+                                dot3_token: DUMMY_SP,
+                                expr: Box::new(Expr::Paren(ParenExpr {
+                                    span: DUMMY_SP,
+                                    expr: Box::new(Expr::Cond(CondExpr {
+                                        span: DUMMY_SP,
+                                        test: Box::new(is_empty),
+                                        cons: Box::new(Expr::Object(ObjectLit {
+                                            span: DUMMY_SP,
+                                            props: vec![],
+                                        })),
+                                        alt: Box::new(present),
+                                    })),
+                                })),
+                            });
+                        }
                         _ => {
                             // For normal fields, check if conversion is needed
-                            if !self.needs_conversion(&field.ty) {
+                            if !self.needs_from_candid_conversion(&field.ty) {
                                 field_access
                             } else {
                                 // Convert the value using appropriate function
@@ -1340,7 +1668,7 @@ impl<'a> TypeConverter<'a> {
                     });
 
                     // Check if conversion is needed
-                    let value = if !self.needs_conversion(&field.ty) {
+                    let value = if !self.needs_from_candid_conversion(&field.ty) {
                         elem_access
                     } else {
                         // Convert the tuple element
@@ -1493,7 +1821,7 @@ impl<'a> TypeConverter<'a> {
             });
 
             // Convert the field value if needed
-            let value = if !self.needs_conversion(&field.ty) {
+            let value = if !self.needs_from_candid_conversion(&field.ty) {
                 field_access
             } else {
                 let function_name = self.get_from_candid_function_name(&field.ty);
@@ -1589,7 +1917,7 @@ impl<'a> TypeConverter<'a> {
 
     pub fn convert_to_candid(&mut self, expr: &Expr, ty: &Type) -> Expr {
         // For simple types that don't need conversion, return the expression directly
-        if !self.needs_conversion(ty) {
+        if !self.needs_to_candid_conversion(ty) {
             return expr.clone();
         }
 
@@ -1602,6 +1930,11 @@ impl<'a> TypeConverter<'a> {
         self.create_call(&function_name, vec![self.create_arg(expr.clone())])
     }
 
+    /// Returns the imported Candid type of the named type `id`
+    pub fn original_named_type(&mut self, id: &str) -> TsType {
+        self.original_types.get_named_type(id)
+    }
+
     /// Add imports for Candid types
     pub fn add_import_for_original_type_definitions(
         &mut self,
@@ -1625,9 +1958,14 @@ impl<'a> TypeConverter<'a> {
         // Add to processing set to detect recursion
         self.processing_to_candid.insert(ty.clone());
 
-        // Generate parameter type annotation
+        // Generate parameter type annotation, lenient inputs are accepted as `Input<T>`
         let param_name = "value";
-        let param_type_ann = self.create_ts_type_annotation(ty);
+        let param_type_ann =
+            if is_lenient_input(self.env, self.options, ty, true, &mut HashSet::new()) {
+                input_of(self.create_ts_type_annotation(ty))
+            } else {
+                self.create_ts_type_annotation(ty)
+            };
 
         // Generate return type annotation - use Candid type for complex types
         let return_type_ann = self.original_types.get_type(ty);
@@ -1707,55 +2045,79 @@ pub fn convert_multi_return_from_candid(
 ) -> Expr {
     if types.is_empty() {
         // No return value, return void
-        Expr::Lit(Lit::Null(Null { span: DUMMY_SP }))
-    } else if types.len() == 1 {
+        return Expr::Lit(Lit::Null(Null { span: DUMMY_SP }));
+    }
+    if types.len() == 1 {
         // Single return value
-        converter.convert_from_candid(expr, &types[0].typ)
+        return converter.convert_from_candid(expr, &types[0].typ);
+    }
+
+    // Convert each of the multiple return values
+    let values = types
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| {
+            // Access return value by index
+            let elem_expr = Expr::Member(MemberExpr {
+                span: DUMMY_SP,
+                obj: Box::new(expr.clone()),
+                prop: MemberProp::Computed(ComputedPropName {
+                    span: DUMMY_SP,
+                    expr: Box::new(Expr::Lit(Lit::Num(Number {
+                        span: DUMMY_SP,
+                        value: i as f64,
+                        raw: None,
+                    }))),
+                }),
+            });
+
+            // If type doesn't need conversion, use it directly
+            if !converter.needs_from_candid_conversion(&ty.typ) {
+                elem_expr
+            } else {
+                // Convert the return value using the appropriate function
+                let function_name = converter.get_from_candid_function_name(&ty.typ);
+                converter.generate_from_candid_function(&ty.typ, &function_name);
+                Expr::Call(CallExpr {
+                    span: DUMMY_SP,
+                    callee: Callee::Expr(Box::new(Expr::Ident(Ident::new(
+                        function_name.into(),
+                        DUMMY_SP,
+                        SyntaxContext::empty(),
+                    )))),
+                    args: vec![ExprOrSpread {
+                        spread: None,
+                        expr: Box::new(elem_expr),
+                    }],
+                    type_args: None,
+                    ctxt: SyntaxContext::empty(),
+                })
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let multi_return = if converter.options.named_returns {
+        // Named return values in an object, e.g. { count: result[0], items: result[1] }
+        Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props: return_value_names(converter.options, types)
+                .iter()
+                .zip(values)
+                .map(|(name, value)| {
+                    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                        key: PropName::Ident(get_ident_guarded_keyword_ok(name).into()),
+                        value: Box::new(value),
+                    })))
+                })
+                .collect(),
+        })
     } else {
         // Multiple return values in a tuple
         Expr::Array(ArrayLit {
             span: DUMMY_SP,
-            elems: types
-                .iter()
-                .enumerate()
-                .map(|(i, ty)| {
-                    // Access return value by index
-                    let elem_expr = Expr::Member(MemberExpr {
-                        span: DUMMY_SP,
-                        obj: Box::new(expr.clone()),
-                        prop: MemberProp::Computed(ComputedPropName {
-                            span: DUMMY_SP,
-                            expr: Box::new(Expr::Lit(Lit::Num(Number {
-                                span: DUMMY_SP,
-                                value: i as f64,
-                                raw: None,
-                            }))),
-                        }),
-                    });
-
-                    // If type doesn't need conversion, use it directly
-                    let value = if !converter.needs_conversion(&ty.typ) {
-                        elem_expr
-                    } else {
-                        // Convert the return value using the appropriate function
-                        let function_name = converter.get_from_candid_function_name(&ty.typ);
-                        converter.generate_from_candid_function(&ty.typ, &function_name);
-                        Expr::Call(CallExpr {
-                            span: DUMMY_SP,
-                            callee: Callee::Expr(Box::new(Expr::Ident(Ident::new(
-                                function_name.into(),
-                                DUMMY_SP,
-                                SyntaxContext::empty(),
-                            )))),
-                            args: vec![ExprOrSpread {
-                                spread: None,
-                                expr: Box::new(elem_expr),
-                            }],
-                            type_args: None,
-                            ctxt: SyntaxContext::empty(),
-                        })
-                    };
-
+            elems: values
+                .into_iter()
+                .map(|value| {
                     Some(ExprOrSpread {
                         spread: None,
                         expr: Box::new(value),
@@ -1763,5 +2125,10 @@ pub fn convert_multi_return_from_candid(
                 })
                 .collect(),
         })
+    };
+
+    if converter.options.freeze_results {
+        return converter.create_call("Object.freeze", vec![converter.create_arg(multi_return)]);
     }
+    multi_return
 }
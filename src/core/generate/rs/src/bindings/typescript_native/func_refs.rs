@@ -0,0 +1,359 @@
+use super::compile_wrapper::create_call_stmts;
+use super::conversion_functions_generator::TypeConverter;
+use super::naming::argument_names;
+use super::utils::{contains_unicode_characters, get_ident_guarded, get_ident_guarded_keyword_ok};
+use candid::types::{Function, TypeEnv, TypeInner};
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use crate::GenerateActorOptions;
+
+/// The name of the factory of the IDL types of the named services and functions, in the declarations.
+pub const IDL_REFERENCE_TYPES: &str = "idlReferenceTypes";
+
+/// The name of the IDL factories of the named services, in the declarations.
+pub const IDL_FACTORIES: &str = "idlFactories";
+
+/// Returns the named function types that get a callable constructor.
+fn named_functions<'a>(
+    env: &'a TypeEnv,
+    options: &GenerateActorOptions,
+) -> Vec<(&'a str, &'a Function)> {
+    if !options.func_refs {
+        return vec![];
+    }
+    env.0
+        .iter()
+        .filter(|(id, _)| options.type_override(id.as_str()).is_none())
+        .filter_map(|(id, ty)| match ty.as_ref() {
+            TypeInner::Func(func) => Some((id.as_str(), func)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Adds a callable constructor for each named function type, named after the type.
+/// Given a function reference and an agent, it returns a function of the type,
+/// which calls the referenced method with the IDL of the type and converts the arguments and the result:
+///
+/// ```ts
+/// export function callback(func: [Principal, string], agent: Agent): callback {
+///     const actor = Actor.createActor<Record<string, _callback>>(({ IDL })=>IDL.Service({
+///             [func[1]]: idlReferenceTypes({ IDL }).callback
+///         }), {
+///         agent,
+///         canisterId: func[0]
+///     });
+///     return async (arg0)=>{
+///         const result = await actor[func[1]](arg0);
+///         return from_candid_Block_n1(result);
+///     };
+/// }
+/// ```
+pub fn add_func_callables(env: &TypeEnv, module: &mut Module, converter: &mut TypeConverter) {
+    for (id, func) in named_functions(env, converter.options()) {
+        let arg_names = argument_names(converter.options(), &func.args);
+        let method = method_name();
+        let callee = Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::new(ident_expr("actor")),
+            prop: MemberProp::Computed(ComputedPropName {
+                span: DUMMY_SP,
+                expr: Box::new(method.clone()),
+            }),
+        });
+        let call_stmts = create_call_stmts(func, &arg_names, callee, method_name(), converter);
+
+        // ({ IDL }) => IDL.Service({ [func[1]]: idlReferenceTypes({ IDL }).<id> })
+        let idl_object = || {
+            Expr::Object(ObjectLit {
+                span: DUMMY_SP,
+                props: vec![PropOrSpread::Prop(Box::new(Prop::Shorthand(ident("IDL"))))],
+            })
+        };
+        let func_idl = Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::new(call(ident_expr(IDL_REFERENCE_TYPES), vec![idl_object()])),
+            prop: if contains_unicode_characters(id) {
+                MemberProp::Computed(ComputedPropName {
+                    span: DUMMY_SP,
+                    expr: Box::new(Expr::Lit(Lit::Str(Str {
+                        span: DUMMY_SP,
+                        value: id.into(),
+                        raw: None,
+                    }))),
+                })
+            } else {
+                MemberProp::Ident(get_ident_guarded_keyword_ok(id).into())
+            },
+        });
+        let interface_factory = Expr::Arrow(ArrowExpr {
+            span: DUMMY_SP,
+            params: vec![Pat::Object(ObjectPat {
+                span: DUMMY_SP,
+                props: vec![ObjectPatProp::Assign(AssignPatProp {
+                    span: DUMMY_SP,
+                    key: BindingIdent {
+                        id: ident("IDL"),
+                        type_ann: None,
+                    },
+                    value: None,
+                })],
+                optional: false,
+                type_ann: None,
+            })],
+            body: Box::new(BlockStmtOrExpr::Expr(Box::new(call(
+                member_expr(ident_expr("IDL"), "Service"),
+                vec![Expr::Object(ObjectLit {
+                    span: DUMMY_SP,
+                    props: vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                        key: PropName::Computed(ComputedPropName {
+                            span: DUMMY_SP,
+                            expr: Box::new(method),
+                        }),
+                        value: Box::new(func_idl),
+                    })))],
+                })],
+            )))),
+            is_async: false,
+            is_generator: false,
+            type_params: None,
+            return_type: None,
+            ctxt: SyntaxContext::empty(),
+        });
+
+        // const actor = Actor.createActor<Record<string, _<id>>>(factory, { agent, canisterId: func[0] });
+        let create_actor = Expr::Call(CallExpr {
+            span: DUMMY_SP,
+            callee: Callee::Expr(Box::new(member_expr(ident_expr("Actor"), "createActor"))),
+            args: vec![
+                interface_factory,
+                Expr::Object(ObjectLit {
+                    span: DUMMY_SP,
+                    props: vec![
+                        PropOrSpread::Prop(Box::new(Prop::Shorthand(ident("agent")))),
+                        PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                            key: PropName::Ident(IdentName::new("canisterId".into(), DUMMY_SP)),
+                            value: Box::new(func_element(0)),
+                        }))),
+                    ],
+                }),
+            ]
+            .into_iter()
+            .map(|expr| ExprOrSpread {
+                spread: None,
+                expr: Box::new(expr),
+            })
+            .collect(),
+            type_args: Some(Box::new(TsTypeParamInstantiation {
+                span: DUMMY_SP,
+                params: vec![Box::new(type_ref(
+                    "Record",
+                    vec![
+                        TsType::TsKeywordType(TsKeywordType {
+                            span: DUMMY_SP,
+                            kind: TsKeywordTypeKind::TsStringKeyword,
+                        }),
+                        converter.original_named_type(id),
+                    ],
+                ))],
+            })),
+            ctxt: SyntaxContext::empty(),
+        });
+
+        // return async (arg0, ...) => { ... };
+        let callable = Expr::Arrow(ArrowExpr {
+            span: DUMMY_SP,
+            params: arg_names
+                .iter()
+                .map(|name| {
+                    Pat::Ident(BindingIdent {
+                        id: ident(name),
+                        type_ann: None,
+                    })
+                })
+                .collect(),
+            body: Box::new(BlockStmtOrExpr::BlockStmt(BlockStmt {
+                span: DUMMY_SP,
+                stmts: call_stmts,
+                ctxt: SyntaxContext::empty(),
+            })),
+            is_async: true,
+            is_generator: false,
+            type_params: None,
+            return_type: None,
+            ctxt: SyntaxContext::empty(),
+        });
+
+        let body = BlockStmt {
+            span: DUMMY_SP,
+            stmts: vec![
+                Stmt::Decl(Decl::Var(Box::new(VarDecl {
+                    span: DUMMY_SP,
+                    kind: VarDeclKind::Const,
+                    declare: false,
+                    decls: vec![VarDeclarator {
+                        span: DUMMY_SP,
+                        name: Pat::Ident(BindingIdent {
+                            id: ident("actor"),
+                            type_ann: None,
+                        }),
+                        init: Some(Box::new(create_actor)),
+                        definite: false,
+                    }],
+                    ctxt: SyntaxContext::empty(),
+                }))),
+                Stmt::Return(ReturnStmt {
+                    span: DUMMY_SP,
+                    arg: Some(Box::new(callable)),
+                }),
+            ],
+            ctxt: SyntaxContext::empty(),
+        };
+        module
+            .body
+            .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                span: DUMMY_SP,
+                decl: Decl::Fn(callable_constructor(id, Some(body))),
+            })));
+    }
+}
+
+/// Declares the callable constructors of the named function types, e.g. in the interface file.
+pub fn add_func_callable_declarations(
+    env: &TypeEnv,
+    options: &GenerateActorOptions,
+    module: &mut Module,
+) {
+    for (id, _) in named_functions(env, options) {
+        module
+            .body
+            .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                span: DUMMY_SP,
+                decl: Decl::Fn(callable_constructor(id, None)),
+            })));
+    }
+}
+
+// function <id>(func: [Principal, string], agent: Agent): <id>
+fn callable_constructor(id: &str, body: Option<BlockStmt>) -> FnDecl {
+    let func_ref = TsType::TsTupleType(TsTupleType {
+        span: DUMMY_SP,
+        elem_types: vec![
+            type_ref("Principal", vec![]),
+            TsType::TsKeywordType(TsKeywordType {
+                span: DUMMY_SP,
+                kind: TsKeywordTypeKind::TsStringKeyword,
+            }),
+        ]
+        .into_iter()
+        .map(|ty| TsTupleElement {
+            span: DUMMY_SP,
+            label: None,
+            ty: Box::new(ty),
+        })
+        .collect(),
+    });
+    FnDecl {
+        ident: get_ident_guarded(id),
+        declare: body.is_none(),
+        function: Box::new(Function {
+            params: vec![("func", func_ref), ("agent", type_ref("Agent", vec![]))]
+                .into_iter()
+                .map(|(name, ty)| Param {
+                    span: DUMMY_SP,
+                    decorators: vec![],
+                    pat: Pat::Ident(BindingIdent {
+                        id: ident(name),
+                        type_ann: Some(Box::new(TsTypeAnn {
+                            span: DUMMY_SP,
+                            type_ann: Box::new(ty),
+                        })),
+                    }),
+                })
+                .collect(),
+            decorators: vec![],
+            span: DUMMY_SP,
+            body,
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: Some(Box::new(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+                    span: DUMMY_SP,
+                    type_name: TsEntityName::Ident(get_ident_guarded(id)),
+                    type_params: None,
+                })),
+            })),
+            ctxt: SyntaxContext::empty(),
+        }),
+    }
+}
+
+// func[1], the name of the referenced method
+fn method_name() -> Expr {
+    func_element(1)
+}
+
+fn func_element(index: usize) -> Expr {
+    Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(ident_expr("func")),
+        prop: MemberProp::Computed(ComputedPropName {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Lit(Lit::Num(Number {
+                span: DUMMY_SP,
+                value: index as f64,
+                raw: None,
+            }))),
+        }),
+    })
+}
+
+fn call(callee: Expr, args: Vec<Expr>) -> Expr {
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(callee)),
+        args: args
+            .into_iter()
+            .map(|expr| ExprOrSpread {
+                spread: None,
+                expr: Box::new(expr),
+            })
+            .collect(),
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    })
+}
+
+fn member_expr(obj: Expr, prop: &str) -> Expr {
+    Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(obj),
+        prop: MemberProp::Ident(IdentName::new(prop.into(), DUMMY_SP)),
+    })
+}
+
+fn ident(name: &str) -> Ident {
+    Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty())
+}
+
+fn ident_expr(name: &str) -> Expr {
+    Expr::Ident(ident(name))
+}
+
+fn type_ref(name: &str, params: Vec<TsType>) -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::Ident(ident(name)),
+        type_params: if params.is_empty() {
+            None
+        } else {
+            Some(Box::new(TsTypeParamInstantiation {
+                span: DUMMY_SP,
+                params: params.into_iter().map(Box::new).collect(),
+            }))
+        },
+    })
+}
@@ -0,0 +1,446 @@
+use super::comments::PosCursor;
+use super::new_typescript_native_types::add_type_definitions;
+use super::utils::{EnumDeclarations, render_ast};
+use candid::types::{ArgType, Type, TypeEnv, TypeInner};
+use candid_parser::syntax::IDLMergedProg;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use swc_core::common::comments::SingleThreadedComments;
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use crate::GenerateActorOptions;
+
+/// The namespace the bindings import the shared ICRC module as.
+pub const ICRC_NAMESPACE: &str = "icrc";
+
+/// The name of the shared ICRC module with the default options.
+pub const ICRC_MODULE: &str = "icrc";
+
+/// The well-known types of the ICRC-1, ICRC-2 and ICRC-3 standards.
+const ICRC_DID: &str = include_str!("icrc.did");
+
+/// The standard services, in the order the actor interface extends them.
+const ICRC_SERVICES: [&str; 2] = ["Icrc1", "Icrc2"];
+
+/// The ICRC-1 textual encoding of accounts, appended to the generated ICRC module.
+/// The owner of the decoded accounts is converted by `account_owner`, see [`account_owner_function`].
+/// See https://github.com/dfinity/ICRC-1/blob/main/standards/ICRC-1/TextualEncoding.md
+const ACCOUNT_ENCODING: &str = r#"function account_checksum(owner: Principal, subaccount: Uint8Array): string {
+    const bytes = new Uint8Array([
+        ...owner.toUint8Array(),
+        ...subaccount
+    ]);
+    let crc = -1;
+    for (const byte of bytes){
+        crc ^= byte;
+        for(let i = 0; i < 8; i++){
+            crc = (crc >>> 1) ^ (0xedb88320 & -(crc & 1));
+        }
+    }
+    crc = (crc ^ -1) >>> 0;
+    const alphabet = "abcdefghijklmnopqrstuvwxyz234567";
+    const checksum = [
+        crc >>> 24,
+        (crc >>> 16) & 0xff,
+        (crc >>> 8) & 0xff,
+        crc & 0xff
+    ];
+    let bits = 0;
+    let value = 0;
+    let result = "";
+    for (const byte of checksum){
+        value = (value << 8) | byte;
+        bits += 8;
+        while(bits >= 5){
+            result += alphabet[(value >>> (bits - 5)) & 31];
+            bits -= 5;
+        }
+    }
+    if (bits > 0) {
+        result += alphabet[(value << (5 - bits)) & 31];
+    }
+    return result;
+}
+/**
+ * Encodes an account in the ICRC-1 textual format (`<owner>-<checksum>.<subaccount>`).
+ * Accounts without a subaccount, or with the default subaccount, are encoded as the owner principal.
+ */
+export function encodeAccount(account: Account): string {
+    const owner = Principal.from(account.owner);
+    const subaccount = account.subaccount;
+    if (subaccount === undefined || subaccount.every((byte)=>byte === 0)) {
+        return owner.toText();
+    }
+    const hex = Array.from(subaccount, (byte)=>byte.toString(16).padStart(2, "0")).join("").replace(/^0+/, "");
+    return owner.toText() + "-" + account_checksum(owner, subaccount) + "." + hex;
+}
+/**
+ * Decodes an account from the ICRC-1 textual format.
+ * Throws if the text is not a valid account encoding.
+ */
+export function decodeAccount(text: string): Account {
+    const [ownerAndChecksum, hex] = text.split(".");
+    if (hex === undefined) {
+        return {
+            owner: account_owner(Principal.fromText(text))
+        };
+    }
+    const separator = ownerAndChecksum.lastIndexOf("-");
+    const owner = Principal.fromText(ownerAndChecksum.slice(0, separator));
+    const subaccount = Uint8Array.from(hex.padStart(64, "0").match(/../g) ?? [], (byte)=>parseInt(byte, 16));
+    if (separator < 0 || hex.length === 0 || hex.length > 64 || hex.startsWith("0") || account_checksum(owner, subaccount) !== ownerAndChecksum.slice(separator + 1)) {
+        throw new Error("Invalid account: " + text);
+    }
+    return {
+        owner: account_owner(owner),
+        subaccount
+    };
+}
+"#;
+
+thread_local! {
+    /// The standard types, parsed once.
+    static STANDARD_ENV: (TypeEnv, IDLMergedProg) = {
+        let (env, _, prog) = crate::parser::check_str("icrc.did", ICRC_DID)
+            .expect("The ICRC standard types must be valid Candid");
+        (env, prog)
+    };
+}
+
+/// Whether the bindings share the standard types of the ICRC module.
+/// The module has no lenient input types nor branded types, so the bindings generated with them
+/// keep their own types.
+pub fn shares_icrc_types(options: &GenerateActorOptions) -> bool {
+    options.icrc_types && !options.input_types && !options.branded_types
+}
+
+/// Returns the name of the shared ICRC module, e.g. `icrc` or `icrc-camel`.
+/// The options shaping the standard types are part of the name, so that the bindings generated
+/// in the same directory with different options do not share the same module.
+pub fn icrc_module_name(options: &GenerateActorOptions) -> String {
+    let mut name = ICRC_MODULE.to_string();
+    for (enabled, suffix) in [
+        (options.map_types, "-maps"),
+        (options.camel_case, "-camel"),
+        (options.readonly_types, "-readonly"),
+        (options.principal_text, "-text"),
+    ] {
+        if enabled {
+            name.push_str(suffix);
+        }
+    }
+    name
+}
+
+/// Checks that the service name does not collide with the name of the shared ICRC module.
+pub fn check_service_name(
+    service_name: &str,
+    options: &GenerateActorOptions,
+) -> Result<(), String> {
+    if shares_icrc_types(options)
+        && (service_name == ICRC_MODULE || service_name == icrc_module_name(options))
+    {
+        return Err(format!(
+            "The service name `{}` collides with the shared ICRC module. Rename the Candid file or disable the icrcTypes option.",
+            service_name
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the named types of `env` that are standard types, mapped to the name of the standard type.
+/// Records and variants are recognized by their structure, other types (e.g. `Tokens`) also by their name.
+pub fn standard_types(env: &TypeEnv, options: &GenerateActorOptions) -> BTreeMap<String, String> {
+    let mut standard_types = BTreeMap::new();
+    if !shares_icrc_types(options) {
+        return standard_types;
+    }
+
+    STANDARD_ENV.with(|(standard_env, _)| {
+        for (id, ty) in env.0.iter() {
+            let standard_type = standard_env.0.iter().find(|(standard_id, standard_ty)| {
+                let structural = match standard_ty.as_ref() {
+                    TypeInner::Record(_) | TypeInner::Variant(_) => true,
+                    TypeInner::Service(_) => return false,
+                    _ => false,
+                };
+                (structural || id.as_str() == standard_id.as_str())
+                    && types_equal(env, ty, standard_env, standard_ty, &mut HashSet::new())
+            });
+            if let Some((standard_id, _)) = standard_type {
+                standard_types.insert(id.as_str().to_string(), standard_id.as_str().to_string());
+            }
+        }
+    });
+    standard_types
+}
+
+/// Returns the standard services whose methods are all implemented by the service `serv`.
+pub fn implemented_services(
+    env: &TypeEnv,
+    serv: &[(String, Type)],
+    options: &GenerateActorOptions,
+) -> Vec<String> {
+    if !shares_icrc_types(options) {
+        return vec![];
+    }
+
+    STANDARD_ENV.with(|(standard_env, _)| {
+        ICRC_SERVICES
+            .iter()
+            .filter(|name| {
+                let Ok(standard_service) = standard_env.find_type(&(**name).into()) else {
+                    return false;
+                };
+                let TypeInner::Service(methods) = standard_service.as_ref() else {
+                    return false;
+                };
+                methods.iter().all(|(method, standard_ty)| {
+                    serv.iter().any(|(m, ty)| {
+                        m == method
+                            && types_equal(env, ty, standard_env, standard_ty, &mut HashSet::new())
+                    })
+                })
+            })
+            .map(|name| name.to_string())
+            .collect()
+    })
+}
+
+/// Structural equality of a type of `env` and a type of `other_env`, ignoring the names of named types.
+/// `assumed` holds the pairs of named types assumed to be equal, to terminate on recursive types.
+fn types_equal(
+    env: &TypeEnv,
+    ty: &Type,
+    other_env: &TypeEnv,
+    other: &Type,
+    assumed: &mut HashSet<(String, String)>,
+) -> bool {
+    match (ty.as_ref(), other.as_ref()) {
+        (TypeInner::Var(id), TypeInner::Var(other_id)) => {
+            if !assumed.insert((id.as_str().to_string(), other_id.as_str().to_string())) {
+                return true;
+            }
+            match (env.rec_find_type(id), other_env.rec_find_type(other_id)) {
+                (Ok(ty), Ok(other)) => types_equal(env, ty, other_env, other, assumed),
+                _ => false,
+            }
+        }
+        (TypeInner::Var(id), _) => env
+            .rec_find_type(id)
+            .is_ok_and(|ty| types_equal(env, ty, other_env, other, assumed)),
+        (_, TypeInner::Var(other_id)) => other_env
+            .rec_find_type(other_id)
+            .is_ok_and(|other| types_equal(env, ty, other_env, other, assumed)),
+        (TypeInner::Opt(ty), TypeInner::Opt(other))
+        | (TypeInner::Vec(ty), TypeInner::Vec(other)) => {
+            types_equal(env, ty, other_env, other, assumed)
+        }
+        (TypeInner::Record(fields), TypeInner::Record(other_fields))
+        | (TypeInner::Variant(fields), TypeInner::Variant(other_fields)) => {
+            fields.len() == other_fields.len()
+                && fields.iter().zip(other_fields).all(|(field, other_field)| {
+                    field.id == other_field.id
+                        && types_equal(env, &field.ty, other_env, &other_field.ty, assumed)
+                })
+        }
+        (TypeInner::Func(func), TypeInner::Func(other_func)) => {
+            func.modes == other_func.modes
+                && args_equal(env, &func.args, other_env, &other_func.args, assumed)
+                && args_equal(env, &func.rets, other_env, &other_func.rets, assumed)
+        }
+        (TypeInner::Service(methods), TypeInner::Service(other_methods)) => {
+            methods.len() == other_methods.len()
+                && methods
+                    .iter()
+                    .zip(other_methods)
+                    .all(|((name, ty), (other_name, other))| {
+                        name == other_name && types_equal(env, ty, other_env, other, assumed)
+                    })
+        }
+        (TypeInner::Class(..), _) | (_, TypeInner::Class(..)) => false,
+        (inner, other_inner) => inner == other_inner,
+    }
+}
+
+fn args_equal(
+    env: &TypeEnv,
+    args: &[ArgType],
+    other_env: &TypeEnv,
+    other_args: &[ArgType],
+    assumed: &mut HashSet<(String, String)>,
+) -> bool {
+    args.len() == other_args.len()
+        && args
+            .iter()
+            .zip(other_args)
+            .all(|(arg, other_arg)| types_equal(env, &arg.typ, other_env, &other_arg.typ, assumed))
+}
+
+/// Returns a reference to the type `name` of the shared ICRC module, e.g. `icrc.Account`.
+pub fn icrc_type_ref(name: &str) -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::TsQualifiedName(Box::new(TsQualifiedName {
+            span: DUMMY_SP,
+            left: TsEntityName::Ident(Ident::new(
+                ICRC_NAMESPACE.into(),
+                DUMMY_SP,
+                SyntaxContext::empty(),
+            )),
+            right: IdentName::new(name.into(), DUMMY_SP),
+        })),
+        type_params: None,
+    })
+}
+
+/// Returns an `extends` clause for the interface of the standard service `name`, e.g. `icrc.Icrc1Interface`.
+pub fn icrc_service_extends(name: &str) -> TsExprWithTypeArgs {
+    TsExprWithTypeArgs {
+        span: DUMMY_SP,
+        expr: Box::new(Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::new(Expr::Ident(Ident::new(
+                ICRC_NAMESPACE.into(),
+                DUMMY_SP,
+                SyntaxContext::empty(),
+            ))),
+            prop: MemberProp::Ident(IdentName::new(
+                format!("{}Interface", name).into(),
+                DUMMY_SP,
+            )),
+        })),
+        type_args: None,
+    }
+}
+
+/// Compiles the shared ICRC module, containing the standard types and the account text encoding.
+pub fn compile_icrc(options: &GenerateActorOptions) -> String {
+    // Only the options that shape the standard types apply, the module has no other imports
+    let options = GenerateActorOptions {
+        map_types: options.map_types,
+        camel_case: options.camel_case,
+        readonly_types: options.readonly_types,
+        principal_text: options.principal_text,
+        ..Default::default()
+    };
+    let mut enum_declarations: EnumDeclarations = HashMap::new();
+    let mut module = Module {
+        span: DUMMY_SP,
+        body: vec![],
+        shebang: None,
+    };
+
+    module
+        .body
+        .push(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+            span: DUMMY_SP,
+            specifiers: vec![ImportSpecifier::Named(ImportNamedSpecifier {
+                span: DUMMY_SP,
+                local: Ident::new("Principal".into(), DUMMY_SP, SyntaxContext::empty()),
+                imported: None,
+                is_type_only: false,
+            })],
+            src: Box::new(Str {
+                span: DUMMY_SP,
+                value: "@icp-sdk/core/principal".into(),
+                raw: None,
+            }),
+            type_only: false,
+            with: None,
+            phase: Default::default(),
+        })));
+
+    let mut comments = SingleThreadedComments::default();
+    let mut cursor = PosCursor::new();
+    let mut top_level_nodes = (&mut enum_declarations, &mut comments, &mut cursor, &options);
+    STANDARD_ENV.with(|(env, prog)| {
+        add_type_definitions(&mut top_level_nodes, env, &mut module, prog, "");
+    });
+    module.body.push(ModuleItem::Stmt(Stmt::Decl(Decl::Fn(
+        account_owner_function(&options),
+    ))));
+
+    render_ast(&module, &comments) + ACCOUNT_ENCODING
+}
+
+/// Returns the function converting the owner of the decoded accounts, as text if principals
+/// are represented as text:
+///
+/// ```ts
+/// function account_owner(owner: Principal): string {
+///     return owner.toText();
+/// }
+/// ```
+fn account_owner_function(options: &GenerateActorOptions) -> FnDecl {
+    let owner = Expr::Ident(Ident::new("owner".into(), DUMMY_SP, SyntaxContext::empty()));
+    let (value, return_type) = if options.principal_text {
+        let to_text = Expr::Call(CallExpr {
+            span: DUMMY_SP,
+            callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+                span: DUMMY_SP,
+                obj: Box::new(owner),
+                prop: MemberProp::Ident(IdentName::new("toText".into(), DUMMY_SP)),
+            }))),
+            args: vec![],
+            type_args: None,
+            ctxt: SyntaxContext::empty(),
+        });
+        let string_type = TsType::TsKeywordType(TsKeywordType {
+            span: DUMMY_SP,
+            kind: TsKeywordTypeKind::TsStringKeyword,
+        });
+        (to_text, string_type)
+    } else {
+        (owner, principal_type())
+    };
+
+    FnDecl {
+        ident: Ident::new("account_owner".into(), DUMMY_SP, SyntaxContext::empty()),
+        declare: false,
+        function: Box::new(Function {
+            params: vec![Param {
+                span: DUMMY_SP,
+                decorators: vec![],
+                pat: Pat::Ident(BindingIdent {
+                    id: Ident::new("owner".into(), DUMMY_SP, SyntaxContext::empty()),
+                    type_ann: Some(Box::new(TsTypeAnn {
+                        span: DUMMY_SP,
+                        type_ann: Box::new(principal_type()),
+                    })),
+                }),
+            }],
+            decorators: vec![],
+            span: DUMMY_SP,
+            body: Some(BlockStmt {
+                span: DUMMY_SP,
+                stmts: vec![Stmt::Return(ReturnStmt {
+                    span: DUMMY_SP,
+                    arg: Some(Box::new(value)),
+                })],
+                ctxt: SyntaxContext::empty(),
+            }),
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: Some(Box::new(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::new(return_type),
+            })),
+            ctxt: SyntaxContext::empty(),
+        }),
+    }
+}
+
+// Principal
+fn principal_type() -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::Ident(Ident::new(
+            "Principal".into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )),
+        type_params: None,
+    })
+}
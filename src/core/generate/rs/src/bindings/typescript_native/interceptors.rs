@@ -0,0 +1,136 @@
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use super::runtime::Runtime;
+use crate::GenerateActorOptions;
+
+/// The function wrapping the actor with the interceptors, in the wrapper.
+const WITH_INTERCEPTORS: &str = "with_interceptors";
+
+/// The interceptors of the calls.
+pub const INTERCEPTORS: Runtime = Runtime {
+    enabled: |options| options.interceptors,
+    types: r#"/**
+ * A method of an actor intercepted by the `interceptors` of `createActor`.
+ */
+export interface InterceptedMethod {
+    readonly name: string;
+    readonly mode: "query" | "composite_query" | "update" | "oneway";
+}
+/**
+ * Hooks invoked around the calls of an actor, e.g. for logging, metrics or tracing.
+ * The arguments and the results of the calls are converted to Candid.
+ * The `before` hooks run in order, the `after` and `onError` hooks in reverse order.
+ */
+export interface Interceptor {
+    before?(method: InterceptedMethod, args: readonly unknown[]): void | Promise<void>;
+    after?(method: InterceptedMethod, result: unknown): void | Promise<void>;
+    onError?(method: InterceptedMethod, error: unknown): void | Promise<void>;
+}
+"#,
+    classes: &[],
+    implementation: r#"function with_interceptors<T>(actor: ActorSubclass<T>, interceptors: Interceptor[] = []): ActorSubclass<T> {
+    if (interceptors.length === 0) {
+        return actor;
+    }
+    return wrap_methods(actor, (name, func, call)=>async (...args)=>{
+            const method = {
+                name,
+                mode: call_mode(func)
+            };
+            for (const interceptor of interceptors){
+                await interceptor.before?.(method, args);
+            }
+            let result;
+            try {
+                result = await call(...args);
+            } catch (error) {
+                for (const interceptor of [...interceptors].reverse()){
+                    await interceptor.onError?.(method, error);
+                }
+                throw error;
+            }
+            for (const interceptor of [...interceptors].reverse()){
+                await interceptor.after?.(method, result);
+            }
+            return result;
+        });
+}
+"#,
+};
+
+/// Returns the `interceptors` property of the `CreateActorOptions` interface, if enabled:
+///
+/// ```ts
+/// interceptors?: Interceptor[];
+/// ```
+pub fn interceptors_property(options: &GenerateActorOptions) -> Option<TsTypeElement> {
+    if !options.interceptors {
+        return None;
+    }
+    Some(TsTypeElement::TsPropertySignature(TsPropertySignature {
+        span: DUMMY_SP,
+        readonly: false,
+        key: Box::new(Expr::Ident(Ident::new(
+            "interceptors".into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        ))),
+        computed: false,
+        optional: true,
+        type_ann: Some(Box::new(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::new(TsType::TsArrayType(TsArrayType {
+                span: DUMMY_SP,
+                elem_type: Box::new(TsType::TsTypeRef(TsTypeRef {
+                    span: DUMMY_SP,
+                    type_name: TsEntityName::Ident(Ident::new(
+                        "Interceptor".into(),
+                        DUMMY_SP,
+                        SyntaxContext::empty(),
+                    )),
+                    type_params: None,
+                })),
+            })),
+        })),
+    }))
+}
+
+/// Wraps the actor created by `createActor` with the configured interceptors, if enabled:
+///
+/// ```ts
+/// with_interceptors(actor, options.interceptors)
+/// ```
+pub fn with_interceptors(options: &GenerateActorOptions, actor: Expr) -> Expr {
+    if !options.interceptors {
+        return actor;
+    }
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(Expr::Ident(Ident::new(
+            WITH_INTERCEPTORS.into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )))),
+        args: vec![
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(actor),
+            },
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(Expr::Member(MemberExpr {
+                    span: DUMMY_SP,
+                    obj: Box::new(Expr::Ident(Ident::new(
+                        "options".into(),
+                        DUMMY_SP,
+                        SyntaxContext::empty(),
+                    ))),
+                    prop: MemberProp::Ident(IdentName::new("interceptors".into(), DUMMY_SP)),
+                })),
+            },
+        ],
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    })
+}
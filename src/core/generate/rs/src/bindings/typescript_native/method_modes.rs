@@ -0,0 +1,94 @@
+use super::naming::member_name;
+use super::utils::get_ident_guarded;
+use candid::types::{FuncMode, Function, Type, TypeEnv, TypeInner};
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::ast::*;
+
+use crate::GenerateActorOptions;
+
+/// The name of the static table of the method modes, on the actor classes.
+pub const METHOD_MODES: &str = "methodModes";
+
+/// Returns the call mode of a method, as named in Candid: `query`, `composite_query`, `update` or `oneway`.
+pub fn method_mode(func: &Function) -> &'static str {
+    match func.modes.first() {
+        Some(FuncMode::Query) => "query",
+        Some(FuncMode::CompositeQuery) => "composite_query",
+        Some(FuncMode::Oneway) => "oneway",
+        None => "update",
+    }
+}
+
+/// Returns the function type of a method, following a named function type.
+pub fn method_function<'a>(env: &'a TypeEnv, ty: &'a Type) -> Option<&'a Function> {
+    match ty.as_ref() {
+        TypeInner::Func(func) => Some(func),
+        TypeInner::Var(id) => match env.rec_find_type(id).ok()?.as_ref() {
+            TypeInner::Func(func) => Some(func),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the JSDoc tag of the mode of a method, e.g. `@query`, if the method modes are exposed.
+pub fn method_mode_tag(env: &TypeEnv, options: &GenerateActorOptions, ty: &Type) -> Option<String> {
+    if !options.method_modes {
+        return None;
+    }
+    method_function(env, ty).map(|func| format!("@{}", method_mode(func)))
+}
+
+/// Returns the static table of the method modes of a service, if the method modes are exposed:
+///
+/// ```ts
+/// static readonly methodModes = {
+///     greet: "query",
+///     set_greeting: "update"
+/// } as const;
+/// ```
+pub fn method_modes_table(
+    env: &TypeEnv,
+    options: &GenerateActorOptions,
+    serv: &[(String, Type)],
+) -> Option<ClassMember> {
+    if !options.method_modes {
+        return None;
+    }
+    let props = serv
+        .iter()
+        .filter_map(|(method_id, ty)| {
+            let func = method_function(env, ty)?;
+            Some(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(get_ident_guarded(&member_name(options, method_id)).into()),
+                value: Box::new(Expr::Lit(Lit::Str(Str {
+                    span: DUMMY_SP,
+                    value: method_mode(func).into(),
+                    raw: None,
+                }))),
+            }))))
+        })
+        .collect();
+
+    Some(ClassMember::ClassProp(ClassProp {
+        span: DUMMY_SP,
+        key: PropName::Ident(IdentName::new(METHOD_MODES.into(), DUMMY_SP)),
+        value: Some(Box::new(Expr::TsConstAssertion(TsConstAssertion {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Object(ObjectLit {
+                span: DUMMY_SP,
+                props,
+            })),
+        }))),
+        type_ann: None,
+        is_static: true,
+        decorators: vec![],
+        accessibility: None,
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+        readonly: true,
+        declare: false,
+        definite: false,
+    }))
+}
@@ -0,0 +1,46 @@
+use super::runtime::Runtime;
+use crate::GenerateActorOptions;
+
+/// The wrapping of the methods of an actor, shared by the interceptors, the retry policy, the query cache
+/// and the oneway receipts.
+/// The wrappers also get the options of the calls made with `withOptions`.
+pub const WRAP_METHODS: Runtime = Runtime {
+    enabled: wraps_methods,
+    types: "",
+    classes: &[],
+    implementation: r#"type Call = (...args: unknown[]) => Promise<unknown>;
+function call_mode(func: IDL.FuncClass): "query" | "composite_query" | "update" | "oneway" {
+    for (const mode of [
+        "query",
+        "composite_query",
+        "oneway"
+    ] as const){
+        if (func.annotations.includes(mode)) {
+            return mode;
+        }
+    }
+    return "update";
+}
+function wrap_methods<T>(actor: ActorSubclass<T>, wrap: (methodName: string, func: IDL.FuncClass, call: Call, options?: CallConfig) => Call): ActorSubclass<T> {
+    const funcs = new Map(Actor.interfaceOf(actor)._fields);
+    return new Proxy(actor, {
+        get (target, property, receiver) {
+            const method = Reflect.get(target, property, receiver);
+            const func = typeof property === "string" ? funcs.get(property) : undefined;
+            if (typeof property !== "string" || func === undefined || typeof method !== "function") {
+                return method;
+            }
+            // Keep `withOptions`, so that the calls with options are wrapped too
+            return Object.assign(wrap(property, func, method as Call), {
+                withOptions: (options: CallConfig)=>wrap(property, func, (method as ActorMethod).withOptions(options), options)
+            });
+        }
+    });
+}
+"#,
+};
+
+/// Whether the methods of the actors created by `createActor` are wrapped, e.g. by the interceptors.
+pub fn wraps_methods(options: &GenerateActorOptions) -> bool {
+    options.interceptors || options.retry_policy || options.query_cache || options.oneway_receipts
+}
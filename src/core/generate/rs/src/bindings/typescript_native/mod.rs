@@ -1,11 +1,27 @@
 //! Ported and adapted from https://github.com/dfinity/candid/pull/672
 
+mod branded;
+mod call_options;
+mod canister_errors;
+mod certified;
 mod comments;
 pub mod compile;
 mod compile_interface;
 mod compile_wrapper;
 mod conversion_functions_generator;
+mod func_refs;
+pub mod icrc;
+mod interceptors;
+mod method_modes;
+mod method_wrappers;
+pub mod naming;
 mod new_typescript_native_types;
+mod oneway;
+mod or_throw;
 mod original_typescript_types;
 mod preamble;
+mod query_cache;
+mod retry;
+mod runtime;
+pub mod type_overrides;
 mod utils;
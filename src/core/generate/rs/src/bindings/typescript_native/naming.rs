@@ -0,0 +1,265 @@
+use super::call_options::WITH_OPTIONS;
+use super::certified::CERTIFIED;
+use super::compile_wrapper::actor_service;
+use super::method_modes::method_function;
+use super::or_throw::or_throw_member_name;
+use super::preamble::input::BIGINT_INPUT;
+use super::query_cache::INVALIDATE;
+use super::utils::{contains_unicode_characters, get_ident_guarded};
+use candid::idl_hash;
+use candid::types::{ArgType, Label, Type, TypeEnv, TypeInner};
+use std::collections::{HashMap, HashSet};
+
+use crate::GenerateActorOptions;
+
+/// Converts a snake_case Candid name to camelCase, keeping leading and trailing underscores.
+/// Names that are not plain identifiers are returned unchanged.
+pub fn to_camel_case(name: &str) -> String {
+    let trimmed = name.trim_matches('_');
+    if trimmed.is_empty() || contains_unicode_characters(name) {
+        return name.to_string();
+    }
+
+    let start = name.len() - name.trim_start_matches('_').len();
+    let end = start + trimmed.len();
+
+    let mut result = String::with_capacity(name.len());
+    result.push_str(&name[..start]);
+    for (i, part) in trimmed
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .enumerate()
+    {
+        if i == 0 {
+            result.push_str(part);
+            continue;
+        }
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            result.extend(first.to_uppercase());
+            result.push_str(chars.as_str());
+        }
+    }
+    result.push_str(&name[end..]);
+    result
+}
+
+/// Returns the TypeScript name of a Candid record field or method.
+pub fn member_name(options: &GenerateActorOptions, name: &str) -> String {
+    if options.camel_case {
+        to_camel_case(name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Returns the TypeScript name of a Candid record field. Hashed labels (e.g. `42 : nat`) are named
+/// after the name of the label dictionary with the same hash, or keyed as `_42_` otherwise.
+pub fn field_name(options: &GenerateActorOptions, label: &Label) -> String {
+    match label {
+        Label::Named(name) => member_name(options, name),
+        Label::Id(n) => match options.label_names.iter().find(|name| idl_hash(name) == *n) {
+            Some(name) => member_name(options, name),
+            None => format!("_{}_", n),
+        },
+        Label::Unnamed(n) => format!("_{}_", n),
+    }
+}
+
+/// Names used in the body of the generated methods, which arguments must not shadow.
+const RESERVED_ARGUMENT_NAMES: [&str; 12] = [
+    "result",
+    "actor",
+    "func",
+    "some",
+    "none",
+    "isNone",
+    "isSome",
+    "unwrap",
+    "candid_some",
+    "candid_none",
+    "Principal",
+    BIGINT_INPUT,
+];
+
+/// Returns the parameter names of the arguments of a method: the Candid names of the arguments,
+/// or their position (`arg0`, `arg1`, ...) for unnamed arguments and names that are not identifiers.
+/// Keywords and names that would shadow the generated helpers are guarded, duplicates are numbered.
+pub fn argument_names(options: &GenerateActorOptions, args: &[ArgType]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(args.len());
+    for (i, arg) in args.iter().enumerate() {
+        let name = arg
+            .name
+            .as_deref()
+            .map(|name| member_name(options, name))
+            .filter(|name| !contains_unicode_characters(name))
+            .filter(|name| !name.starts_with(|c: char| c.is_ascii_digit()))
+            .map(|name| {
+                let shadows_helper = RESERVED_ARGUMENT_NAMES.contains(&name.as_str())
+                    || name.starts_with("to_candid_")
+                    || name.starts_with("from_candid_")
+                    || options
+                        .type_overrides
+                        .iter()
+                        .any(|o| o.to_candid == name || o.from_candid == name);
+                if shadows_helper {
+                    format!("{}_", name)
+                } else {
+                    get_ident_guarded(&name).sym.to_string()
+                }
+            })
+            .unwrap_or_else(|| format!("arg{}", i));
+        let name = if names.contains(&name) {
+            format!("{}_{}", name, i)
+        } else {
+            name
+        };
+        names.push(name);
+    }
+    names
+}
+
+/// Returns the members of the generated actor classes besides the methods of the service, with the
+/// option adding them, if any.
+fn class_members(options: &GenerateActorOptions) -> Vec<(&'static str, Option<&'static str>)> {
+    let mut members = vec![("constructor", None), ("actor", None)];
+    if options.certified_queries {
+        members.push((CERTIFIED, Some("certifiedQueries")));
+    }
+    if options.call_options {
+        members.push((WITH_OPTIONS, Some("callOptions")));
+    }
+    if options.query_cache {
+        members.push((INVALIDATE, Some("queryCache")));
+    }
+    members
+}
+
+/// Checks that no two names of the same record or service map to the same camelCase name.
+pub fn check_camel_case_collisions(
+    env: &TypeEnv,
+    actor: &Option<Type>,
+    options: &GenerateActorOptions,
+) -> Result<(), String> {
+    let mut visited = HashSet::new();
+    for ty in env.0.values() {
+        check_type(options, ty, &mut visited)?;
+    }
+    if let Some(actor) = actor {
+        check_type(options, actor, &mut visited)?;
+    }
+    Ok(())
+}
+
+fn check_type(
+    options: &GenerateActorOptions,
+    ty: &Type,
+    visited: &mut HashSet<Type>,
+) -> Result<(), String> {
+    if !visited.insert(ty.clone()) {
+        return Ok(());
+    }
+
+    match ty.as_ref() {
+        TypeInner::Opt(inner) | TypeInner::Vec(inner) => check_type(options, inner, visited),
+        TypeInner::Record(fields) => {
+            // Hashed labels are keyed as `_<hash>_` or named after the label names
+            check_names(fields.iter().map(|field| {
+                let name = match field.id.as_ref() {
+                    Label::Named(name) => name.clone(),
+                    Label::Id(n) | Label::Unnamed(n) => n.to_string(),
+                };
+                (name, field_name(options, &field.id))
+            }))?;
+            fields
+                .iter()
+                .try_for_each(|field| check_type(options, &field.ty, visited))
+        }
+        TypeInner::Variant(fields) => fields
+            .iter()
+            .try_for_each(|field| check_type(options, &field.ty, visited)),
+        TypeInner::Func(func) => func
+            .args
+            .iter()
+            .chain(func.rets.iter())
+            .try_for_each(|arg| check_type(options, &arg.typ, visited)),
+        TypeInner::Service(methods) => {
+            check_names(
+                methods
+                    .iter()
+                    .map(|(name, _)| (name.clone(), to_camel_case(name))),
+            )?;
+            methods
+                .iter()
+                .try_for_each(|(_, method)| check_type(options, method, visited))
+        }
+        TypeInner::Class(args, inner) => {
+            args.iter()
+                .try_for_each(|arg| check_type(options, &arg.typ, visited))?;
+            check_type(options, inner, visited)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_names(names: impl Iterator<Item = (String, String)>) -> Result<(), String> {
+    let mut seen: HashMap<String, String> = HashMap::new();
+    for (name, camel_case_name) in names {
+        if let Some(other) = seen.insert(camel_case_name.clone(), name.clone()) {
+            return Err(format!(
+                "The Candid names `{}` and `{}` both map to `{}` in camelCase. Disable the camelCase option or rename one of them.",
+                other, name, camel_case_name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that no method of a service maps to another member of its generated actor class and
+/// interface, e.g. `withOptions` or the `...OrThrow` variant of another method.
+pub fn check_class_members(
+    env: &TypeEnv,
+    actor: &Option<Type>,
+    options: &GenerateActorOptions,
+) -> Result<(), String> {
+    let services = env
+        .0
+        .values()
+        .chain(actor.iter())
+        .filter_map(|ty| actor_service(env, ty));
+    for methods in services {
+        // The generated members, with what they are and the option adding them
+        let mut members: HashMap<String, (String, Option<&str>)> = class_members(options)
+            .into_iter()
+            .map(|(name, option)| {
+                let member = "a member of the generated actor class".to_string();
+                (name.to_string(), (member, option))
+            })
+            .collect();
+        for (method_id, ty) in methods {
+            if let Some(name) = method_function(env, ty)
+                .and_then(|func| or_throw_member_name(env, options, method_id, func))
+            {
+                let member = format!("the `...OrThrow` variant of `{}`", method_id);
+                members.insert(name, (member, Some("orThrowMethods")));
+            }
+        }
+        for (method_id, _) in methods {
+            let name = get_ident_guarded(&member_name(options, method_id))
+                .sym
+                .to_string();
+            if let Some((member, option)) = members.get(&name) {
+                return Err(format!(
+                    "The Candid method `{}` maps to `{}`, which is {}. Rename the method{}.",
+                    method_id,
+                    name,
+                    member,
+                    option
+                        .map(|option| format!(" or disable the `{}` option", option))
+                        .unwrap_or_default()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,14 +1,29 @@
 use super::super::javascript::is_tuple;
+use super::branded;
+use super::call_options::with_options_signature;
+use super::certified::certified_signature;
 use super::comments::add_comments;
 use super::conversion_functions_generator::{TopLevelNodes, TypeConverter};
+use super::icrc;
+use super::method_modes::{method_function, method_mode_tag};
+use super::naming::{argument_names, field_name, member_name};
+use super::oneway::{is_oneway_submission, oneway_receipt_type};
+use super::or_throw::or_throw_signature;
 use super::original_typescript_types::create_typed_array_type;
+use super::preamble::input::input_of;
+use super::preamble::service_ref::service_ref_of;
+use super::query_cache::invalidate_signature;
 use super::utils::{get_ident_guarded, get_ident_guarded_keyword_ok};
-use candid::types::{Field, Function, Label, Type, TypeEnv, TypeInner};
+use candid::types::internal::TypeKey;
+use candid::types::{ArgType, Field, Function, Label, Type, TypeEnv, TypeInner};
 use candid_parser::syntax::{self, IDLMergedProg, IDLType};
+use std::collections::HashSet;
 use swc_core::common::Span;
 use swc_core::common::{DUMMY_SP, SyntaxContext};
 use swc_core::ecma::ast::*;
 
+use crate::GenerateActorOptions;
+
 // Helper function to determine if a type is recursively optional
 pub fn is_recursive_optional(
     env: &TypeEnv,
@@ -44,11 +59,271 @@ pub fn is_recursive_optional(
     }
 }
 
+/// Returns the key and value types of an association list (`vec record { K; V }`)
+/// that should be represented as a `Map<K, V>`, either because maps are enabled
+/// for all association lists or for the named type `name`.
+/// Only the keys represented by primitive values are supported, since a `Map` compares
+/// the other keys (e.g. `Principal` or `Uint8Array`) by identity.
+pub fn map_entry_types(
+    env: &TypeEnv,
+    options: &GenerateActorOptions,
+    ty: &Type,
+    name: Option<&str>,
+) -> Option<(Type, Type)> {
+    let enabled = options.map_types
+        || name.is_some_and(|name| options.map_type_names.iter().any(|n| n == name));
+    if !enabled {
+        return None;
+    }
+    let TypeInner::Vec(entry) = ty.as_ref() else {
+        return None;
+    };
+    let entry = match entry.as_ref() {
+        TypeInner::Var(id) => env.rec_find_type(id).ok()?,
+        _ => entry,
+    };
+    match entry.as_ref() {
+        TypeInner::Record(fields)
+            if fields.len() == 2 && is_tuple(entry) && is_map_key(env, options, &fields[0].ty) =>
+        {
+            Some((fields[0].ty.clone(), fields[1].ty.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether the keys of type `ty` are represented by primitive values,
+/// which a `Map` compares by value.
+fn is_map_key(env: &TypeEnv, options: &GenerateActorOptions, ty: &Type) -> bool {
+    use TypeInner::*;
+
+    match ty.as_ref() {
+        Bool | Nat | Int | Nat8 | Nat16 | Nat32 | Nat64 | Int8 | Int16 | Int32 | Int64
+        | Float32 | Float64 | Text => true,
+        // Principals represented as text
+        Principal => options.principal_text,
+        Var(id) if options.type_override(id).is_none() => env
+            .rec_find_type(id)
+            .is_ok_and(|ty| is_map_key(env, options, ty)),
+        _ => false,
+    }
+}
+
+/// Returns whether the input representation of `ty` (`Input<T>`) accepts more values than its
+/// output representation, i.e. whether it contains a `bigint` or a `Principal`, when lenient
+/// inputs are enabled. Blobs also accept `number[]` as inputs, but the declarations accept it
+/// as well, so blobs only count if `with_blob` is set.
+pub fn is_lenient_input(
+    env: &TypeEnv,
+    options: &GenerateActorOptions,
+    ty: &Type,
+    with_blob: bool,
+    visited: &mut HashSet<String>,
+) -> bool {
+    use TypeInner::*;
+
+    if !options.input_types {
+        return false;
+    }
+    match ty.as_ref() {
+        Nat | Int | Nat64 | Int64 | Func(_) => true,
+        // Principals represented as text are not lenient
+        Principal | Service(_) => !options.principal_text,
+        Opt(inner) => is_lenient_input(env, options, inner, with_blob, visited),
+        Vec(inner) => {
+            let inner_ty = match inner.as_ref() {
+                Var(id) if options.type_override(id).is_none() => {
+                    env.rec_find_type(id).unwrap_or(inner)
+                }
+                _ => inner,
+            };
+            match inner_ty.as_ref() {
+                // Typed arrays are kept as they are
+                Nat8 => with_blob,
+                Nat16 | Nat32 | Nat64 | Int8 | Int16 | Int32 | Int64 => false,
+                _ => is_lenient_input(env, options, inner, with_blob, visited),
+            }
+        }
+        Record(fields) | Variant(fields) => fields
+            .iter()
+            .any(|field| is_lenient_input(env, options, &field.ty, with_blob, visited)),
+        Var(id) if options.type_override(id).is_some() => false,
+        Var(id) => {
+            if !visited.insert(id.as_str().to_string()) {
+                return false;
+            }
+            env.rec_find_type(id)
+                .is_ok_and(|ty| is_lenient_input(env, options, ty, with_blob, visited))
+        }
+        _ => false,
+    }
+}
+
+/// Whether `ty` contains an array or a tuple, which have a readonly representation when readonly
+/// types are enabled. Records and variant objects only count if `with_objects` is set, e.g. to
+/// find the values to freeze. Typed arrays are kept as they are.
+pub fn contains_mutable_value(
+    env: &TypeEnv,
+    options: &GenerateActorOptions,
+    ty: &Type,
+    with_objects: bool,
+    visited: &mut HashSet<String>,
+) -> bool {
+    use TypeInner::*;
+
+    match ty.as_ref() {
+        Opt(inner) => contains_mutable_value(env, options, inner, with_objects, visited),
+        Vec(inner) => {
+            let inner_ty = match inner.as_ref() {
+                Var(id) if options.type_override(id).is_none() => {
+                    env.rec_find_type(id).unwrap_or(inner)
+                }
+                _ => inner,
+            };
+            !matches!(
+                inner_ty.as_ref(),
+                Nat8 | Nat16 | Nat32 | Nat64 | Int8 | Int16 | Int32 | Int64
+            )
+        }
+        Record(_) if is_tuple(ty) => true,
+        Record(_) if with_objects => true,
+        Variant(fields) if with_objects => !fields.iter().all(|f| matches!(f.ty.as_ref(), Null)),
+        Record(fields) | Variant(fields) => fields
+            .iter()
+            .any(|field| contains_mutable_value(env, options, &field.ty, with_objects, visited)),
+        Var(id) if options.type_override(id).is_some() => false,
+        Var(id) => {
+            if !visited.insert(id.as_str().to_string()) {
+                return false;
+            }
+            env.rec_find_type(id)
+                .is_ok_and(|ty| contains_mutable_value(env, options, ty, with_objects, visited))
+        }
+        _ => false,
+    }
+}
+
+/// Wraps `ty` in `readonly` if readonly types are enabled, e.g. `readonly [bigint, string]`.
+fn readonly_operator(options: &GenerateActorOptions, ty: TsType) -> TsType {
+    if !options.readonly_types {
+        return ty;
+    }
+    TsType::TsTypeOperator(TsTypeOperator {
+        span: DUMMY_SP,
+        op: TsTypeOperatorOp::ReadOnly,
+        type_ann: Box::new(ty),
+    })
+}
+
+/// Whether the arguments of a method are passed as a single named-arguments object.
+pub fn uses_named_args(options: &GenerateActorOptions, args: &[ArgType]) -> bool {
+    options.named_args && args.len() > 1
+}
+
+/// Creates the type of the named-arguments object, with the [argument names](argument_names)
+/// as properties, e.g. `{ to: Account; amount: bigint }`.
+pub fn create_named_args_type(
+    top_level_nodes: &mut TopLevelNodes,
+    env: &TypeEnv,
+    args: &[ArgType],
+    names: &[String],
+) -> TsType {
+    TsType::TsTypeLit(TsTypeLit {
+        span: DUMMY_SP,
+        members: names
+            .iter()
+            .zip(args)
+            .map(|(name, arg)| {
+                TsTypeElement::TsPropertySignature(TsPropertySignature {
+                    span: DUMMY_SP,
+                    readonly: false,
+                    key: Box::new(Expr::Ident(get_ident_guarded_keyword_ok(name))),
+                    computed: false,
+                    optional: false,
+                    type_ann: Some(Box::new(TsTypeAnn {
+                        span: DUMMY_SP,
+                        type_ann: Box::new(convert_input_type(top_level_nodes, env, &arg.typ)),
+                    })),
+                })
+            })
+            .collect(),
+    })
+}
+
+/// Returns the property names of the object returned by methods with multiple return values,
+/// using the Candid names of the values and their position (`_0`, `_1`, ...) as a fallback.
+pub fn return_value_names(options: &GenerateActorOptions, rets: &[ArgType]) -> Vec<String> {
+    rets.iter()
+        .enumerate()
+        .map(|(i, ret)| match &ret.name {
+            Some(name) => member_name(options, name),
+            None => format!("_{}", i),
+        })
+        .collect()
+}
+
+/// Creates the type of multiple return values: a tuple, or an object with the
+/// [return value names](return_value_names) if named returns are enabled.
+pub fn create_multi_return_type(
+    top_level_nodes: &mut TopLevelNodes,
+    env: &TypeEnv,
+    rets: &[ArgType],
+) -> TsType {
+    let options = top_level_nodes.3;
+    if !options.named_returns {
+        let tuple = TsType::TsTupleType(TsTupleType {
+            span: DUMMY_SP,
+            elem_types: rets
+                .iter()
+                .map(|ret| TsTupleElement {
+                    span: DUMMY_SP,
+                    label: None,
+                    ty: Box::new(convert_type(top_level_nodes, env, &ret.typ, None, true)),
+                })
+                .collect(),
+        });
+        return readonly_operator(options, tuple);
+    }
+
+    TsType::TsTypeLit(TsTypeLit {
+        span: DUMMY_SP,
+        members: return_value_names(options, rets)
+            .iter()
+            .zip(rets)
+            .map(|(name, ret)| {
+                TsTypeElement::TsPropertySignature(TsPropertySignature {
+                    span: DUMMY_SP,
+                    readonly: options.readonly_types,
+                    key: Box::new(Expr::Ident(get_ident_guarded_keyword_ok(name))),
+                    computed: false,
+                    optional: false,
+                    type_ann: Some(Box::new(TsTypeAnn {
+                        span: DUMMY_SP,
+                        type_ann: Box::new(convert_type(
+                            top_level_nodes,
+                            env,
+                            &ret.typ,
+                            None,
+                            true,
+                        )),
+                    })),
+                })
+            })
+            .collect(),
+    })
+}
+
+/// Returns the name of the input type of the named type `id`.
+pub fn input_type_name(id: &str) -> String {
+    format!("{}Input", id)
+}
+
 // Create TS interface from Candid service
 pub fn create_interface_from_service(
     top_level_nodes: &mut TopLevelNodes,
     env: &TypeEnv,
     service_name: &str,
+    error_class: &str,
     syntax: Option<&IDLType>,
     serv: &[(String, Type)],
 ) -> TsInterfaceDecl {
@@ -56,13 +331,16 @@ pub fn create_interface_from_service(
         Some(IDLType::ServT(syntax_serv)) => Some(syntax_serv),
         _ => None,
     };
-    let members = serv
+    let mut members: Vec<TsTypeElement> = serv
         .iter()
         .map(|(method_id, method_ty)| {
-            let span = syntax_serv
+            let mut docs = syntax_serv
                 .and_then(|bindings| bindings.iter().find(|b| &b.id == method_id))
-                .map(|b| add_comments(top_level_nodes, b.docs.as_ref()))
-                .unwrap_or(DUMMY_SP);
+                .map(|b| b.docs.clone())
+                .unwrap_or_default();
+            docs.extend(method_mode_tag(env, top_level_nodes.3, method_ty));
+            let span = add_comments(top_level_nodes, &docs);
+            let method_name = member_name(top_level_nodes.3, method_id);
 
             match method_ty.as_ref() {
                 TypeInner::Func(func) => {
@@ -70,7 +348,7 @@ pub fn create_interface_from_service(
                 }
                 TypeInner::Var(var_id) => TsTypeElement::TsPropertySignature(TsPropertySignature {
                     span,
-                    key: Box::new(Expr::Ident(get_ident_guarded(method_id))),
+                    key: Box::new(Expr::Ident(get_ident_guarded(&method_name))),
                     computed: false,
                     optional: false,
                     readonly: false,
@@ -87,6 +365,32 @@ pub fn create_interface_from_service(
             }
         })
         .collect();
+    // Inline services have no interface to return
+    if !service_name.is_empty() {
+        for (method_id, method_ty) in serv {
+            if let Some(func) = method_function(env, method_ty)
+                && let Some(signature) = or_throw_signature(
+                    top_level_nodes,
+                    env,
+                    service_name,
+                    error_class,
+                    method_id,
+                    func,
+                )
+            {
+                members.push(signature);
+            }
+        }
+        if top_level_nodes.3.certified_queries {
+            members.push(certified_signature(service_name));
+        }
+        if top_level_nodes.3.call_options {
+            members.push(with_options_signature(service_name));
+        }
+        if top_level_nodes.3.query_cache {
+            members.push(invalidate_signature());
+        }
+    }
 
     TsInterfaceDecl {
         span: DUMMY_SP,
@@ -101,6 +405,37 @@ pub fn create_interface_from_service(
     }
 }
 
+pub fn convert_input_type_with_converter(
+    converter: &mut TypeConverter,
+    env: &TypeEnv,
+    ty: &Type,
+) -> TsType {
+    let mut top_level_nodes = converter.top_level_nodes();
+    convert_input_type(&mut top_level_nodes, env, ty)
+}
+
+/// Converts the type of a method argument, to `Input<T>` if it accepts lenient inputs.
+/// Named types use their `<Name>Input` alias.
+pub fn convert_input_type(top_level_nodes: &mut TopLevelNodes, env: &TypeEnv, ty: &Type) -> TsType {
+    if !is_lenient_input(env, top_level_nodes.3, ty, true, &mut HashSet::new()) {
+        return convert_type(top_level_nodes, env, ty, None, true);
+    }
+    match ty.as_ref() {
+        TypeInner::Var(id) if has_input_type(env, id) => TsType::TsTypeRef(TsTypeRef {
+            span: DUMMY_SP,
+            type_name: TsEntityName::Ident(get_ident_guarded(&input_type_name(id.as_str()))),
+            type_params: None,
+        }),
+        _ => input_of(convert_type(top_level_nodes, env, ty, None, true)),
+    }
+}
+
+// Services and functions are inlined as references, so they have no `<Name>Input` alias
+fn has_input_type(env: &TypeEnv, id: &TypeKey) -> bool {
+    env.rec_find_type(id)
+        .is_ok_and(|ty| !matches!(ty.as_ref(), TypeInner::Service(_) | TypeInner::Func(_)))
+}
+
 pub fn convert_type_with_converter(
     converter: &mut TypeConverter,
     env: &TypeEnv,
@@ -155,20 +490,21 @@ pub fn convert_type(
             span: DUMMY_SP,
             kind: TsKeywordTypeKind::TsNeverKeyword,
         }),
-        Principal => TsType::TsTypeRef(TsTypeRef {
-            span: DUMMY_SP,
-            type_name: TsEntityName::Ident(Ident::new(
-                "Principal".into(),
-                DUMMY_SP,
-                SyntaxContext::empty(),
-            )),
-            type_params: None,
-        }),
+        Principal => create_principal_type(top_level_nodes.3),
         // Reference types
         Var(id) => {
             if is_ref {
                 let ty = env.rec_find_type(id).unwrap();
-                if matches!(ty.as_ref(), Service(_) | Func(_)) {
+                if top_level_nodes.3.service_refs && matches!(ty.as_ref(), Service(_)) {
+                    // Named services are referenced with their interface, e.g. `ServiceRef<brokerInterface>`
+                    service_ref_of(TsType::TsTypeRef(TsTypeRef {
+                        span: DUMMY_SP,
+                        type_name: TsEntityName::Ident(service_interface_ident(service_type_name(
+                            env, id,
+                        ))),
+                        type_params: None,
+                    }))
+                } else if matches!(ty.as_ref(), Service(_) | Func(_)) {
                     convert_type(top_level_nodes, env, ty, None, false)
                 } else {
                     TsType::TsTypeRef(TsTypeRef {
@@ -188,7 +524,13 @@ pub fn convert_type(
         // Optional types
         Opt(t) => create_opt_type(top_level_nodes, env, t, syntax, is_ref),
         // Vector types
-        Vec(t) => create_vector_type(top_level_nodes, env, syntax, t, is_ref),
+        Vec(t) => {
+            let options = top_level_nodes.3;
+            match map_entry_types(env, options, ty, None) {
+                Some((key, value)) => create_map_type(top_level_nodes, env, &key, &value, is_ref),
+                None => create_vector_type(top_level_nodes, env, syntax, t, is_ref),
+            }
+        }
         // Record types
         Record(fs) => create_record_type(top_level_nodes, env, ty, syntax, fs, is_ref),
         // Variant types
@@ -196,15 +538,15 @@ pub fn convert_type(
         Func(_) => create_function_type_ref(),
         // Note: we map to a generic principal type for now
         // see https://github.com/dfinity/candid/issues/606
-        Service(_) => TsType::TsTypeRef(TsTypeRef {
-            span: DUMMY_SP,
-            type_name: TsEntityName::Ident(Ident::new(
-                "Principal".into(),
-                DUMMY_SP,
-                SyntaxContext::empty(),
-            )),
-            type_params: None,
-        }),
+        Service(serv) if top_level_nodes.3.service_refs => {
+            // Inline services are referenced with the literal type of their interface
+            let interface = create_interface_from_service(top_level_nodes, env, "", "", None, serv);
+            service_ref_of(TsType::TsTypeLit(TsTypeLit {
+                span: DUMMY_SP,
+                members: interface.body.body,
+            }))
+        }
+        Service(_) => create_principal_type(top_level_nodes.3),
         // Unsupported types
         Class(_, _) | Knot(_) | Unknown | Future => TsType::TsKeywordType(TsKeywordType {
             span: DUMMY_SP,
@@ -215,6 +557,33 @@ pub fn convert_type(
 
 // Internal functions
 
+// The name of the service type an alias resolves to, e.g. `broker` for `type A = broker;`
+fn service_type_name<'a>(env: &'a TypeEnv, id: &'a TypeKey) -> &'a str {
+    match env.find_type(id).map(|ty| ty.as_ref()) {
+        Ok(TypeInner::Var(inner_id)) => service_type_name(env, inner_id),
+        _ => id.as_str(),
+    }
+}
+
+// Principals are represented as `Principal` objects, or as their text if principal text is enabled
+fn create_principal_type(options: &GenerateActorOptions) -> TsType {
+    if options.principal_text {
+        return TsType::TsKeywordType(TsKeywordType {
+            span: DUMMY_SP,
+            kind: TsKeywordTypeKind::TsStringKeyword,
+        });
+    }
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::Ident(Ident::new(
+            "Principal".into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )),
+        type_params: None,
+    })
+}
+
 fn create_opt_type(
     top_level_nodes: &mut TopLevelNodes,
     env: &TypeEnv,
@@ -355,10 +724,13 @@ fn create_vector_type(
     let ty = match t.as_ref() {
         Var(id) => {
             let ty = env.rec_find_type(id).unwrap();
-            if matches!(
-                ty.as_ref(),
-                Nat8 | Nat16 | Nat32 | Nat64 | Int8 | Int16 | Int32 | Int64
-            ) {
+            // Overridden types keep their user-defined representation
+            if top_level_nodes.3.type_override(id).is_none()
+                && matches!(
+                    ty.as_ref(),
+                    Nat8 | Nat16 | Nat32 | Nat64 | Int8 | Int16 | Int32 | Int64
+                )
+            {
                 ty
             } else {
                 t
@@ -378,10 +750,15 @@ fn create_vector_type(
         Int64 => create_typed_array_type("BigInt64Array"),
         _ => {
             // Generic array type
+            let array_name = if top_level_nodes.3.readonly_types {
+                "ReadonlyArray"
+            } else {
+                "Array"
+            };
             TsType::TsTypeRef(TsTypeRef {
                 span: DUMMY_SP,
                 type_name: TsEntityName::Ident(Ident::new(
-                    "Array".into(),
+                    array_name.into(),
                     DUMMY_SP,
                     SyntaxContext::empty(),
                 )),
@@ -400,6 +777,36 @@ fn create_vector_type(
     }
 }
 
+// Create a Map<K, V> type for association lists
+fn create_map_type(
+    top_level_nodes: &mut TopLevelNodes,
+    env: &TypeEnv,
+    key: &Type,
+    value: &Type,
+    is_ref: bool,
+) -> TsType {
+    let map_name = if top_level_nodes.3.readonly_types {
+        "ReadonlyMap"
+    } else {
+        "Map"
+    };
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::Ident(Ident::new(
+            map_name.into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )),
+        type_params: Some(Box::new(TsTypeParamInstantiation {
+            span: DUMMY_SP,
+            params: vec![
+                Box::new(convert_type(top_level_nodes, env, key, None, is_ref)),
+                Box::new(convert_type(top_level_nodes, env, value, None, is_ref)),
+            ],
+        })),
+    })
+}
+
 fn create_record_type(
     top_level_nodes: &mut TopLevelNodes,
     env: &TypeEnv,
@@ -415,7 +822,7 @@ fn create_record_type(
     let span = DUMMY_SP;
     if is_tuple(ty) {
         // Create tuple type
-        TsType::TsTupleType(TsTupleType {
+        let tuple = TsType::TsTupleType(TsTupleType {
             span,
             elem_types: fs
                 .iter()
@@ -425,7 +832,8 @@ fn create_record_type(
                     ty: Box::new(convert_type(top_level_nodes, env, &f.ty, None, is_ref)),
                 })
                 .collect(),
-        })
+        });
+        readonly_operator(top_level_nodes.3, tuple)
     } else {
         // Create record type
         TsType::TsTypeLit(TsTypeLit {
@@ -476,7 +884,7 @@ fn create_variant_type(
                 .collect();
 
             // Only create enum if it doesn't already exist
-            let (enum_declarations, _, _) = top_level_nodes;
+            let (enum_declarations, _, _, _) = top_level_nodes;
             enum_declarations.entry(fs.to_vec()).or_insert_with(|| {
                 let enum_name = if let Some(name) = type_name {
                     name.to_string()
@@ -538,7 +946,7 @@ fn create_variant_type(
                         // Create the __kind__ property
                         let kind_prop = TsTypeElement::TsPropertySignature(TsPropertySignature {
                             span: DUMMY_SP,
-                            readonly: false,
+                            readonly: top_level_nodes.3.readonly_types,
                             key: Box::new(Expr::Ident(Ident::new(
                                 "__kind__".into(),
                                 DUMMY_SP,
@@ -579,13 +987,16 @@ fn create_variant_type(
     }
 }
 
-// Add all type definitions from the environment
+// Add all type definitions from the environment.
+// The `...OrThrow` methods of the named services throw the `error_class` of the actor.
 pub fn add_type_definitions(
     top_level_nodes: &mut TopLevelNodes,
     env: &TypeEnv,
     module: &mut Module,
     prog: &IDLMergedProg,
+    error_class: &str,
 ) {
+    let standard_types = icrc::standard_types(env, top_level_nodes.3);
     for id in env.0.keys() {
         if let Ok(ty) = env.find_type(id) {
             let syntax = prog.lookup(id.as_str());
@@ -593,6 +1004,70 @@ pub fn add_type_definitions(
             let span = syntax
                 .map(|s| add_comments(top_level_nodes, s.docs.as_ref()))
                 .unwrap_or(DUMMY_SP);
+            if let Some(type_override) = top_level_nodes.3.type_override(id) {
+                // Overridden types are aliases of the imported user-defined type
+                if type_override.type_name != *id {
+                    let type_alias = TsTypeAliasDecl {
+                        span: DUMMY_SP,
+                        declare: false,
+                        id: get_ident_guarded(id.as_str()),
+                        type_params: None,
+                        type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+                            span: DUMMY_SP,
+                            type_name: TsEntityName::Ident(get_ident_guarded(
+                                &type_override.type_name,
+                            )),
+                            type_params: None,
+                        })),
+                    };
+                    module
+                        .body
+                        .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                            span,
+                            decl: Decl::TsTypeAlias(Box::new(type_alias)),
+                        })));
+                }
+                continue;
+            }
+            if has_input_type(env, id)
+                && is_lenient_input(env, top_level_nodes.3, ty, true, &mut HashSet::new())
+            {
+                // Lenient representation of the type accepted by the methods
+                let type_alias = TsTypeAliasDecl {
+                    span: DUMMY_SP,
+                    declare: false,
+                    id: get_ident_guarded(&input_type_name(id.as_str())),
+                    type_params: None,
+                    type_ann: Box::new(input_of(TsType::TsTypeRef(TsTypeRef {
+                        span: DUMMY_SP,
+                        type_name: TsEntityName::Ident(get_ident_guarded(id.as_str())),
+                        type_params: None,
+                    }))),
+                };
+                module
+                    .body
+                    .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                        span: DUMMY_SP,
+                        decl: Decl::TsTypeAlias(Box::new(type_alias)),
+                    })));
+            }
+            if let Some(standard_type) = standard_types.get(id.as_str()) {
+                // Standard types are aliases of the type of the shared ICRC module
+                let type_alias = TsTypeAliasDecl {
+                    span: DUMMY_SP,
+                    declare: false,
+                    id: get_ident_guarded(id.as_str()),
+                    type_params: None,
+                    type_ann: Box::new(icrc::icrc_type_ref(standard_type)),
+                };
+                module
+                    .body
+                    .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                        span,
+                        decl: Decl::TsTypeAlias(Box::new(type_alias)),
+                    })));
+                continue;
+            }
             match ty.as_ref() {
                 TypeInner::Record(_) if !is_tuple(ty) => {
                     // Generate interface for record types
@@ -616,6 +1091,7 @@ pub fn add_type_definitions(
                         top_level_nodes,
                         env,
                         id.as_str(),
+                        error_class,
                         syntax_ty,
                         serv,
                     );
@@ -693,6 +1169,27 @@ pub fn add_type_definitions(
                             decl: Decl::TsTypeAlias(Box::new(type_alias)),
                         })));
                 }
+                _ if branded::is_branded(env, top_level_nodes.3, id.as_str()) => {
+                    // Aliases of primitive types are branded to make them nominal
+                    let type_alias = TsTypeAliasDecl {
+                        span: DUMMY_SP,
+                        declare: false,
+                        id: get_ident_guarded(id.as_str()),
+                        type_params: None,
+                        type_ann: Box::new(branded::create_branded_type(
+                            top_level_nodes,
+                            env,
+                            id.as_str(),
+                            ty,
+                        )),
+                    };
+                    module
+                        .body
+                        .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                            span,
+                            decl: Decl::TsTypeAlias(Box::new(type_alias)),
+                        })));
+                }
                 _ => {
                     // Generate type alias for other types
                     let type_alias = create_type_alias(top_level_nodes, env, id.as_str(), ty);
@@ -787,12 +1284,17 @@ fn create_type_alias(
     id: &str,
     ty: &Type,
 ) -> TsTypeAliasDecl {
+    let options = top_level_nodes.3;
+    let type_ann = match map_entry_types(env, options, ty, Some(id)) {
+        Some((key, value)) => create_map_type(top_level_nodes, env, &key, &value, false),
+        None => convert_type(top_level_nodes, env, ty, None, false),
+    };
     TsTypeAliasDecl {
         span: DUMMY_SP,
         declare: false,
         id: get_ident_guarded(id),
         type_params: None,
-        type_ann: Box::new(convert_type(top_level_nodes, env, ty, None, false)),
+        type_ann: Box::new(type_ann),
     }
 }
 
@@ -804,9 +1306,12 @@ fn create_property_signature(
     syntax: Option<&IDLType>,
     span: Span,
 ) -> TsTypeElement {
+    let options = top_level_nodes.3;
     let field_name = match &*field.id {
-        Label::Named(str) => Box::new(Expr::Ident(get_ident_guarded_keyword_ok(str))),
-        Label::Id(n) | Label::Unnamed(n) => Box::new(Expr::Ident(Ident::new(
+        Label::Named(_) | Label::Id(_) => Box::new(Expr::Ident(get_ident_guarded_keyword_ok(
+            &field_name(options, &field.id),
+        ))),
+        Label::Unnamed(n) => Box::new(Expr::Ident(Ident::new(
             format!("_{}_", n).into(),
             DUMMY_SP,
             SyntaxContext::empty(),
@@ -828,7 +1333,7 @@ fn create_property_signature(
 
     TsTypeElement::TsPropertySignature(TsPropertySignature {
         span,
-        readonly: false,
+        readonly: top_level_nodes.3.readonly_types,
         key: field_name,
         computed: false,
         optional: is_optional,
@@ -886,7 +1391,7 @@ fn create_property_signature_for_variant(
 
     TsTypeElement::TsPropertySignature(TsPropertySignature {
         span,
-        readonly: false,
+        readonly: top_level_nodes.3.readonly_types,
         key: field_name,
         computed: false,
         optional: false,
@@ -905,45 +1410,47 @@ fn create_method_signature(
     func: &Function,
     span: Span,
 ) -> TsTypeElement {
+    let options = top_level_nodes.3;
     // Create parameters
-    let params = func
-        .args
-        .iter()
-        .enumerate()
-        .map(|(i, arg_ty)| {
-            let var_name = arg_ty.name.clone().unwrap_or_else(|| format!("arg{}", i));
-            TsFnParam::Ident(BindingIdent {
-                id: Ident::new(var_name.into(), DUMMY_SP, SyntaxContext::empty()),
-                type_ann: Some(Box::new(TsTypeAnn {
-                    span: DUMMY_SP,
-                    type_ann: Box::new(convert_type(top_level_nodes, env, &arg_ty.typ, None, true)),
-                })),
+    let arg_names = argument_names(options, &func.args);
+    let params = if uses_named_args(options, &func.args) {
+        vec![TsFnParam::Ident(BindingIdent {
+            id: Ident::new("args".into(), DUMMY_SP, SyntaxContext::empty()),
+            type_ann: Some(Box::new(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::new(create_named_args_type(
+                    top_level_nodes,
+                    env,
+                    &func.args,
+                    &arg_names,
+                )),
+            })),
+        })]
+    } else {
+        func.args
+            .iter()
+            .zip(&arg_names)
+            .map(|(arg_ty, var_name)| {
+                TsFnParam::Ident(BindingIdent {
+                    id: Ident::new(var_name.as_str().into(), DUMMY_SP, SyntaxContext::empty()),
+                    type_ann: Some(Box::new(TsTypeAnn {
+                        span: DUMMY_SP,
+                        type_ann: Box::new(convert_input_type(top_level_nodes, env, &arg_ty.typ)),
+                    })),
+                })
             })
-        })
-        .collect();
+            .collect()
+    };
 
     // Create return type
     let return_type = match func.rets.len() {
+        _ if is_oneway_submission(options, func) => oneway_receipt_type(),
         0 => TsType::TsKeywordType(TsKeywordType {
             span: DUMMY_SP,
             kind: TsKeywordTypeKind::TsVoidKeyword,
         }),
         1 => convert_type(top_level_nodes, env, &func.rets[0].typ, None, true),
-        _ => {
-            // Create a tuple type for multiple return values
-            TsType::TsTupleType(TsTupleType {
-                span: DUMMY_SP,
-                elem_types: func
-                    .rets
-                    .iter()
-                    .map(|ret| TsTupleElement {
-                        span: DUMMY_SP,
-                        label: None,
-                        ty: Box::new(convert_type(top_level_nodes, env, &ret.typ, None, true)),
-                    })
-                    .collect(),
-            })
-        }
+        _ => create_multi_return_type(top_level_nodes, env, &func.rets),
     };
 
     // Wrap return type in Promise
@@ -962,7 +1469,9 @@ fn create_method_signature(
 
     TsTypeElement::TsMethodSignature(TsMethodSignature {
         span,
-        key: Box::new(Expr::Ident(get_ident_guarded(method_id))),
+        key: Box::new(Expr::Ident(get_ident_guarded(&member_name(
+            options, method_id,
+        )))),
         computed: false,
         optional: false,
         params,
@@ -1004,21 +1513,8 @@ fn create_function_type(
             kind: TsKeywordTypeKind::TsVoidKeyword,
         }),
         1 => convert_type(top_level_nodes, env, &func.rets[0].typ, None, true),
-        _ => {
-            // Create a tuple type for multiple return values
-            TsType::TsTupleType(TsTupleType {
-                span: DUMMY_SP,
-                elem_types: func
-                    .rets
-                    .iter()
-                    .map(|ret| TsTupleElement {
-                        span: DUMMY_SP,
-                        label: None,
-                        ty: Box::new(convert_type(top_level_nodes, env, &ret.typ, None, true)),
-                    })
-                    .collect(),
-            })
-        }
+        // Multiple return values are returned like the ones of the methods
+        _ => create_multi_return_type(top_level_nodes, env, &func.rets),
     };
 
     let promise_return_type = TsType::TsTypeRef(TsTypeRef {
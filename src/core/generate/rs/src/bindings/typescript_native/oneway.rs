@@ -0,0 +1,218 @@
+use candid::types::{FuncMode, Function, Type, TypeEnv};
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use super::method_modes::method_function;
+use super::runtime::{Runtime, RuntimeClass};
+use crate::GenerateActorOptions;
+
+/// The type of the result of the oneway methods, if they are submitted without waiting.
+pub const ONEWAY_RECEIPT: &str = "OnewayReceipt";
+
+/// The function wrapping the actor with the submission of the oneway calls, in the wrapper.
+const WITH_ONEWAY_RECEIPTS: &str = "with_oneway_receipts";
+
+/// The type of the actors whose oneway methods resolve to their receipt.
+const WITH_ONEWAY_RECEIPTS_TYPE: &str = "WithOnewayReceipts";
+
+/// The receipts of the oneway calls, and their submission in the wrapper.
+pub const ONEWAY_RECEIPTS: Runtime = Runtime {
+    enabled: |options| options.oneway_receipts,
+    types: r#"/**
+ * The receipt of a oneway call, returned once the call is submitted.
+ * The canister does not reply to oneway calls, so traps of the canister are not reported.
+ */
+export interface OnewayReceipt {
+    readonly methodName: string;
+    readonly requestId: RequestId;
+}
+/**
+ * The methods of the actor `T`, whose oneway methods `K` resolve to their receipt.
+ */
+export type WithOnewayReceipts<T, K extends keyof T> = Omit<T, K> & {
+    [P in K]: T[P] extends ActorMethod<infer Args extends unknown[]> ? ActorMethod<Args, OnewayReceipt> : never;
+};
+"#,
+    classes: &[RuntimeClass {
+        docs: r#"/**
+ * Thrown if a oneway call could not be submitted to the canister.
+ */
+"#,
+        name: "OnewaySubmissionError",
+        extends: "Error",
+        fields: &[("methodName", "string"), ("error", "unknown")],
+        message: "The oneway call to ${methodName} could not be submitted: ${error}",
+    }],
+    implementation: r#"function with_oneway_receipts<T, K extends keyof T & string>(actor: ActorSubclass<T>, methodNames: readonly K[], actorOptions?: Partial<ActorConfig>): ActorSubclass<WithOnewayReceipts<T, K>>;
+function with_oneway_receipts<T>(actor: ActorSubclass<T>, methodNames: readonly string[], actorOptions: Partial<ActorConfig> = {}): ActorSubclass<T> {
+    return wrap_methods(actor, (methodName, func, call, callOptions)=>{
+        if (!methodNames.includes(methodName)) {
+            return call;
+        }
+        return async (...args)=>{
+            try {
+                const agent = callOptions?.agent ?? Actor.agentOf(actor);
+                if (agent === undefined) {
+                    throw new Error("The actor has no agent");
+                }
+                const canisterId = Principal.from(callOptions?.canisterId ?? Actor.canisterIdOf(actor));
+                const { requestId, response } = await agent.call(canisterId, {
+                    methodName,
+                    arg: IDL.encode(func.argTypes, args),
+                    effectiveCanisterId: Principal.from(callOptions?.effectiveCanisterId ?? actorOptions.effectiveCanisterId ?? canisterId),
+                    callSync: false
+                });
+                if (!response.ok) {
+                    throw new Error(`${response.status} ${response.statusText}`);
+                }
+                const receipt: OnewayReceipt = {
+                    methodName,
+                    requestId
+                };
+                return receipt;
+            } catch (error) {
+                throw new OnewaySubmissionError(methodName, error);
+            }
+        };
+    });
+}
+"#,
+};
+
+/// Whether the method `func` is submitted without waiting, returning a `OnewayReceipt`.
+pub fn is_oneway_submission(options: &GenerateActorOptions, func: &Function) -> bool {
+    options.oneway_receipts && func.modes.contains(&FuncMode::Oneway)
+}
+
+/// Returns the names of the methods of the service `serv` submitted without waiting.
+pub fn oneway_methods(
+    env: &TypeEnv,
+    options: &GenerateActorOptions,
+    serv: &[(String, Type)],
+) -> Vec<String> {
+    serv.iter()
+        .filter(|(_, ty)| {
+            method_function(env, ty).is_some_and(|f| is_oneway_submission(options, f))
+        })
+        .map(|(method_id, _)| method_id.clone())
+        .collect()
+}
+
+/// Creates the statements that submit a oneway call through the actor, whose oneway methods are
+/// wrapped by `with_oneway_receipts` and typed as resolving to their receipt:
+///
+/// ```ts
+/// return await this.actor.log(arg0);
+/// ```
+pub fn create_submit_stmts(actor_call: Expr) -> Vec<Stmt> {
+    vec![Stmt::Return(ReturnStmt {
+        span: DUMMY_SP,
+        arg: Some(Box::new(Expr::Await(AwaitExpr {
+            span: DUMMY_SP,
+            arg: Box::new(actor_call),
+        }))),
+    })]
+}
+
+/// Returns the `OnewayReceipt` type.
+pub fn oneway_receipt_type() -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::Ident(ident(ONEWAY_RECEIPT)),
+        type_params: None,
+    })
+}
+
+/// Returns the type of the actor whose oneway methods resolve to their receipt, if any:
+///
+/// ```ts
+/// WithOnewayReceipts<_SERVICE, "log" | "notify">
+/// ```
+pub fn oneway_actor_type(actor_type: TsType, oneway: &[String]) -> TsType {
+    if oneway.is_empty() {
+        return actor_type;
+    }
+    let names = oneway
+        .iter()
+        .map(|method_id| {
+            Box::new(TsType::TsLitType(TsLitType {
+                span: DUMMY_SP,
+                lit: TsLit::Str(str_lit(method_id)),
+            }))
+        })
+        .collect();
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::Ident(ident(WITH_ONEWAY_RECEIPTS_TYPE)),
+        type_params: Some(Box::new(TsTypeParamInstantiation {
+            span: DUMMY_SP,
+            params: vec![
+                Box::new(actor_type),
+                Box::new(TsType::TsUnionOrIntersectionType(
+                    TsUnionOrIntersectionType::TsUnionType(TsUnionType {
+                        span: DUMMY_SP,
+                        types: names,
+                    }),
+                )),
+            ],
+        })),
+    })
+}
+
+/// Wraps the actor created by `createActor` with the submission of its oneway methods, if any:
+///
+/// ```ts
+/// with_oneway_receipts(actor, ["log", "notify"], options.actorOptions)
+/// ```
+pub fn with_oneway_receipts(oneway: &[String], actor: Expr) -> Expr {
+    if oneway.is_empty() {
+        return actor;
+    }
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(Expr::Ident(ident(WITH_ONEWAY_RECEIPTS)))),
+        args: vec![
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(actor),
+            },
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(Expr::Array(ArrayLit {
+                    span: DUMMY_SP,
+                    elems: oneway
+                        .iter()
+                        .map(|method_id| {
+                            Some(ExprOrSpread {
+                                spread: None,
+                                expr: Box::new(Expr::Lit(Lit::Str(str_lit(method_id)))),
+                            })
+                        })
+                        .collect(),
+                })),
+            },
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(Expr::Member(MemberExpr {
+                    span: DUMMY_SP,
+                    obj: Box::new(Expr::Ident(ident("options"))),
+                    prop: MemberProp::Ident(IdentName::new("actorOptions".into(), DUMMY_SP)),
+                })),
+            },
+        ],
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    })
+}
+
+fn ident(name: &str) -> Ident {
+    Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty())
+}
+
+fn str_lit(value: &str) -> Str {
+    Str {
+        span: DUMMY_SP,
+        value: value.into(),
+        raw: None,
+    }
+}
@@ -0,0 +1,406 @@
+use super::comments::add_comments;
+use super::conversion_functions_generator::TopLevelNodes;
+use super::naming::member_name;
+use super::new_typescript_native_types::{convert_type, service_interface_ident};
+use super::utils::get_ident_guarded;
+use candid::types::{Field, Function, Label, TypeEnv, TypeInner};
+use swc_core::common::{DUMMY_SP, Span, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use crate::GenerateActorOptions;
+
+/// Returns the `Ok` and `Err` fields of the result of a method, if it returns a `variant { Ok : T; Err : E }`.
+fn ok_err_fields<'a>(
+    env: &'a TypeEnv,
+    options: &GenerateActorOptions,
+    func: &'a Function,
+) -> Option<(&'a Field, &'a Field)> {
+    if !options.or_throw_methods || func.rets.len() != 1 {
+        return None;
+    }
+    let mut ty = &func.rets[0].typ;
+    while let TypeInner::Var(id) = ty.as_ref() {
+        if options.type_override(id.as_str()).is_some() {
+            return None;
+        }
+        ty = env.find_type(id).ok()?;
+    }
+    let TypeInner::Variant(fields) = ty.as_ref() else {
+        return None;
+    };
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|field| matches!(&*field.id, Label::Named(label) if label == name))
+    };
+    // The variants whose tags are all `null` are represented as enums, without payloads
+    let all_null = fields
+        .iter()
+        .all(|f| matches!(f.ty.as_ref(), TypeInner::Null));
+    match (fields.len(), field("Ok"), field("Err")) {
+        (2, Some(ok), Some(err)) if !all_null => Some((ok, err)),
+        _ => None,
+    }
+}
+
+/// Returns the doc comment of the `...OrThrow` variant of a method, typing the error it throws:
+///
+/// ```ts
+/// /**
+///  * Returns the `Ok` payload of `transfer`.
+///  * @throws {LedgerError<Extract<Awaited<ReturnType<ledgerInterface["transfer"]>>, { __kind__: "Err" }>["Err"]>} If the call returns an `Err`.
+///  */
+/// ```
+fn or_throw_docs(
+    top_level_nodes: &mut TopLevelNodes,
+    service_name: &str,
+    error_class: &str,
+    method_id: &str,
+) -> Span {
+    let method_name = member_name(top_level_nodes.3, method_id);
+    let err_type = format!(
+        "Extract<Awaited<ReturnType<{}[\"{}\"]>>, {{ __kind__: \"Err\" }}>[\"Err\"]",
+        service_interface_ident(service_name).sym,
+        method_name
+    );
+    add_comments(
+        top_level_nodes,
+        &[
+            format!("Returns the `Ok` payload of `{}`.", method_name),
+            format!(
+                "@throws {{{}<{}>}} If the call returns an `Err`.",
+                error_class, err_type
+            ),
+        ],
+    )
+}
+
+/// Returns the name of the `...OrThrow` variant of a method.
+fn or_throw_name(options: &GenerateActorOptions, method_id: &str) -> String {
+    format!("{}OrThrow", member_name(options, method_id))
+}
+
+/// Returns the name of the `...OrThrow` variant of a method, if it returns an Ok/Err variant.
+pub fn or_throw_member_name(
+    env: &TypeEnv,
+    options: &GenerateActorOptions,
+    method_id: &str,
+    func: &Function,
+) -> Option<String> {
+    ok_err_fields(env, options, func)?;
+    Some(
+        get_ident_guarded(&or_throw_name(options, method_id))
+            .sym
+            .to_string(),
+    )
+}
+
+/// Returns the name of the error thrown by the `...OrThrow` methods, e.g. `LedgerError`.
+/// It gets a `Service` suffix if the name clashes with a Candid type.
+pub fn error_class_name(env: &TypeEnv, service_name: &str) -> String {
+    let mut chars = service_name.chars();
+    let capitalized = chars
+        .next()
+        .map_or(String::new(), |c| c.to_uppercase().chain(chars).collect());
+    let name = format!("{}Error", capitalized);
+    if env.find_type(&name.as_str().into()).is_ok() {
+        format!("{}ServiceError", capitalized)
+    } else {
+        name
+    }
+}
+
+// ...args: Parameters<helloInterface["greet"]>
+fn args_param(service_name: &str, options: &GenerateActorOptions, method_id: &str) -> RestPat {
+    RestPat {
+        span: DUMMY_SP,
+        dot3_token: DUMMY_SP,
+        arg: Box::new(Pat::Ident(BindingIdent {
+            id: Ident::new("args".into(), DUMMY_SP, SyntaxContext::empty()),
+            type_ann: None,
+        })),
+        type_ann: Some(Box::new(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+                span: DUMMY_SP,
+                type_name: TsEntityName::Ident(Ident::new(
+                    "Parameters".into(),
+                    DUMMY_SP,
+                    SyntaxContext::empty(),
+                )),
+                type_params: Some(Box::new(TsTypeParamInstantiation {
+                    span: DUMMY_SP,
+                    params: vec![Box::new(TsType::TsIndexedAccessType(TsIndexedAccessType {
+                        span: DUMMY_SP,
+                        readonly: false,
+                        obj_type: Box::new(TsType::TsTypeRef(TsTypeRef {
+                            span: DUMMY_SP,
+                            type_name: TsEntityName::Ident(service_interface_ident(service_name)),
+                            type_params: None,
+                        })),
+                        index_type: Box::new(TsType::TsLitType(TsLitType {
+                            span: DUMMY_SP,
+                            lit: TsLit::Str(Str {
+                                span: DUMMY_SP,
+                                value: member_name(options, method_id).into(),
+                                raw: None,
+                            }),
+                        })),
+                    }))],
+                })),
+            })),
+        })),
+    }
+}
+
+// Promise<T>, with the type T of the `Ok` payload
+fn ok_return_type(top_level_nodes: &mut TopLevelNodes, env: &TypeEnv, ok: &Field) -> TsTypeAnn {
+    TsTypeAnn {
+        span: DUMMY_SP,
+        type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+            span: DUMMY_SP,
+            type_name: TsEntityName::Ident(Ident::new(
+                "Promise".into(),
+                DUMMY_SP,
+                SyntaxContext::empty(),
+            )),
+            type_params: Some(Box::new(TsTypeParamInstantiation {
+                span: DUMMY_SP,
+                params: vec![Box::new(convert_type(
+                    top_level_nodes,
+                    env,
+                    &ok.ty,
+                    None,
+                    true,
+                ))],
+            })),
+        })),
+    }
+}
+
+/// Returns the `...OrThrow` variant of a method of a service interface, if it returns an Ok/Err variant.
+/// Its doc comment types the error it throws, see [`or_throw_docs`]:
+///
+/// ```ts
+/// transferOrThrow(...args: Parameters<ledgerInterface["transfer"]>): Promise<bigint>;
+/// ```
+pub fn or_throw_signature(
+    top_level_nodes: &mut TopLevelNodes,
+    env: &TypeEnv,
+    service_name: &str,
+    error_class: &str,
+    method_id: &str,
+    func: &Function,
+) -> Option<TsTypeElement> {
+    let options = top_level_nodes.3;
+    let (ok, _) = ok_err_fields(env, options, func)?;
+    let span = or_throw_docs(top_level_nodes, service_name, error_class, method_id);
+    Some(TsTypeElement::TsMethodSignature(TsMethodSignature {
+        span,
+        key: Box::new(Expr::Ident(get_ident_guarded(&or_throw_name(
+            options, method_id,
+        )))),
+        computed: false,
+        optional: false,
+        params: vec![TsFnParam::Rest(args_param(
+            service_name,
+            options,
+            method_id,
+        ))],
+        type_ann: Some(Box::new(ok_return_type(top_level_nodes, env, ok))),
+        type_params: None,
+    }))
+}
+
+/// Returns the `...OrThrow` variant of a method of an actor class, if it returns an Ok/Err variant.
+/// It returns the `Ok` payload, or throws the error class with the `Err` payload, typed by its doc comment:
+///
+/// ```ts
+/// async transferOrThrow(...args: Parameters<ledgerInterface["transfer"]>): Promise<bigint> {
+///     const result = await this.transfer(...args);
+///     if (result.__kind__ === "Err") {
+///         throw new LedgerError("transfer", result.Err);
+///     }
+///     return result.Ok;
+/// }
+/// ```
+pub fn or_throw_method(
+    top_level_nodes: &mut TopLevelNodes,
+    env: &TypeEnv,
+    service_name: &str,
+    error_class: &str,
+    method_id: &str,
+    func: &Function,
+) -> Option<ClassMember> {
+    let options = top_level_nodes.3;
+    let (ok, _) = ok_err_fields(env, options, func)?;
+    let span = or_throw_docs(top_level_nodes, service_name, error_class, method_id);
+    let ident = |name: &str| Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty());
+    let str_lit = |value: &str| {
+        Expr::Lit(Lit::Str(Str {
+            span: DUMMY_SP,
+            value: value.into(),
+            raw: None,
+        }))
+    };
+    let result_member = |name: &str| {
+        Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::new(Expr::Ident(ident("result"))),
+            prop: MemberProp::Ident(IdentName::new(name.into(), DUMMY_SP)),
+        })
+    };
+
+    // const result = await this.transfer(...args);
+    let call = Expr::Await(AwaitExpr {
+        span: DUMMY_SP,
+        arg: Box::new(Expr::Call(CallExpr {
+            span: DUMMY_SP,
+            callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+                span: DUMMY_SP,
+                obj: Box::new(Expr::This(ThisExpr { span: DUMMY_SP })),
+                prop: MemberProp::Ident(get_ident_guarded(&member_name(options, method_id)).into()),
+            }))),
+            args: vec![ExprOrSpread {
+                spread: Some(DUMMY_SP),
+                expr: Box::new(Expr::Ident(ident("args"))),
+            }],
+            type_args: None,
+            ctxt: SyntaxContext::empty(),
+        })),
+    });
+    let stmts = vec![
+        Stmt::Decl(Decl::Var(Box::new(VarDecl {
+            span: DUMMY_SP,
+            kind: VarDeclKind::Const,
+            declare: false,
+            decls: vec![VarDeclarator {
+                span: DUMMY_SP,
+                name: Pat::Ident(BindingIdent {
+                    id: ident("result"),
+                    type_ann: None,
+                }),
+                init: Some(Box::new(call)),
+                definite: false,
+            }],
+            ctxt: SyntaxContext::empty(),
+        }))),
+        // if (result.__kind__ === "Err") { throw new LedgerError("transfer", result.Err); }
+        Stmt::If(IfStmt {
+            span: DUMMY_SP,
+            test: Box::new(Expr::Bin(BinExpr {
+                span: DUMMY_SP,
+                op: BinaryOp::EqEqEq,
+                left: Box::new(result_member("__kind__")),
+                right: Box::new(str_lit("Err")),
+            })),
+            cons: Box::new(Stmt::Block(BlockStmt {
+                span: DUMMY_SP,
+                stmts: vec![Stmt::Throw(ThrowStmt {
+                    span: DUMMY_SP,
+                    arg: Box::new(Expr::New(NewExpr {
+                        span: DUMMY_SP,
+                        callee: Box::new(Expr::Ident(ident(error_class))),
+                        args: Some(vec![
+                            ExprOrSpread {
+                                spread: None,
+                                expr: Box::new(str_lit(method_id)),
+                            },
+                            ExprOrSpread {
+                                spread: None,
+                                expr: Box::new(result_member("Err")),
+                            },
+                        ]),
+                        type_args: None,
+                        ctxt: SyntaxContext::empty(),
+                    })),
+                })],
+                ctxt: SyntaxContext::empty(),
+            })),
+            alt: None,
+        }),
+        Stmt::Return(ReturnStmt {
+            span: DUMMY_SP,
+            arg: Some(Box::new(result_member("Ok"))),
+        }),
+    ];
+
+    Some(ClassMember::Method(ClassMethod {
+        span,
+        key: PropName::Ident(get_ident_guarded(&or_throw_name(options, method_id)).into()),
+        function: Box::new(swc_core::ecma::ast::Function {
+            params: vec![Param {
+                span: DUMMY_SP,
+                decorators: vec![],
+                pat: Pat::Rest(args_param(service_name, options, method_id)),
+            }],
+            decorators: vec![],
+            span: DUMMY_SP,
+            body: Some(BlockStmt {
+                span: DUMMY_SP,
+                stmts,
+                ctxt: SyntaxContext::empty(),
+            }),
+            is_generator: false,
+            is_async: true,
+            type_params: None,
+            return_type: Some(Box::new(ok_return_type(top_level_nodes, env, ok))),
+            ctxt: SyntaxContext::empty(),
+        }),
+        kind: MethodKind::Method,
+        is_static: false,
+        accessibility: None,
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+    }))
+}
+
+/// Returns the error class thrown by the `...OrThrow` methods for the wrapper, if enabled.
+pub fn or_throw_runtime(
+    env: &TypeEnv,
+    options: &GenerateActorOptions,
+    service_name: &str,
+) -> String {
+    if !options.or_throw_methods {
+        return String::new();
+    }
+    let name = error_class_name(env, service_name);
+    format!(
+        r#"/**
+ * Thrown by the `...OrThrow` methods if the call returned an `Err`, with the `Err` payload.
+ * The `@throws` tag of each method gives the type of its payload.
+ */
+export class {name}<E = unknown> extends Error {{
+    constructor(readonly methodName: string, readonly err: E){{
+        super(`The call to ${{methodName}} returned an error`);
+        this.name = "{name}";
+    }}
+}}
+"#
+    )
+}
+
+/// Returns the declaration of the error class thrown by the `...OrThrow` methods for the interface file, if enabled.
+pub fn or_throw_declarations(
+    env: &TypeEnv,
+    options: &GenerateActorOptions,
+    service_name: &str,
+) -> String {
+    if !options.or_throw_methods {
+        return String::new();
+    }
+    let name = error_class_name(env, service_name);
+    format!(
+        r#"/**
+ * Thrown by the `...OrThrow` methods if the call returned an `Err`, with the `Err` payload.
+ * The `@throws` tag of each method gives the type of its payload.
+ */
+export declare class {name}<E = unknown> extends Error {{
+    readonly methodName: string;
+    readonly err: E;
+    constructor(methodName: string, err: E);
+}}
+"#
+    )
+}
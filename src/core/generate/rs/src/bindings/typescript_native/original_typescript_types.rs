@@ -384,6 +384,20 @@ impl<'a> OriginalTypescriptTypes<'a> {
         }
     }
 
+    /// Returns the imported Candid type of the named type `id`, e.g. `_f` for the function type `f`.
+    pub fn get_named_type(&mut self, id: &str) -> TsType {
+        self.add_required_import(id);
+        TsType::TsTypeRef(TsTypeRef {
+            span: DUMMY_SP,
+            type_name: TsEntityName::Ident(Ident::new(
+                format!("_{}", id).into(),
+                DUMMY_SP,
+                SyntaxContext::empty(),
+            )),
+            type_params: None,
+        })
+    }
+
     pub fn get_type(&mut self, ty: &Type) -> TsType {
         match ty.as_ref() {
             TypeInner::Record(_) | TypeInner::Variant(_) => {
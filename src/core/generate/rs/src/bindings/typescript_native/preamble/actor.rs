@@ -1,11 +1,15 @@
 use swc_core::common::DUMMY_SP;
 use swc_core::ecma::ast::*;
 
+use crate::GenerateActorOptions;
+use crate::bindings::typescript_native::interceptors::interceptors_property;
+use crate::bindings::typescript_native::query_cache::query_cache_property;
+use crate::bindings::typescript_native::retry::retry_policy_property;
 use crate::bindings::typescript_native::utils::get_ident_guarded;
 
 /// Creates the CreateActorOptions interface used by both wrapper and interface files
-pub fn create_actor_options_interface() -> TsInterfaceDecl {
-    TsInterfaceDecl {
+pub fn create_actor_options_interface(options: &GenerateActorOptions) -> TsInterfaceDecl {
+    let mut interface = TsInterfaceDecl {
         span: DUMMY_SP,
         id: "CreateActorOptions".into(),
         declare: false,
@@ -61,5 +65,9 @@ pub fn create_actor_options_interface() -> TsInterfaceDecl {
                 }),
             ],
         },
-    }
+    };
+    interface.body.body.extend(interceptors_property(options));
+    interface.body.body.extend(retry_policy_property(options));
+    interface.body.body.extend(query_cache_property(options));
+    interface
 }
@@ -1,16 +1,102 @@
+use std::collections::BTreeMap;
 use swc_core::common::{DUMMY_SP, SyntaxContext};
 use swc_core::ecma::ast::*;
 
-pub fn interface_imports(module: &mut Module, service_name: &str) {
-    interface_core_agent_imports(module);
-    core_principal_import(module);
+use super::super::icrc::{ICRC_NAMESPACE, icrc_module_name, shares_icrc_types};
+use super::super::method_wrappers::wraps_methods;
+use crate::GenerateActorOptions;
+
+pub fn interface_imports(module: &mut Module, service_name: &str, options: &GenerateActorOptions) {
+    interface_core_agent_imports(module, options);
+    core_principal_import(module, true);
     old_bindings_imports_interface(module, service_name);
+    type_override_imports(module, options, false);
+    icrc_import(module, options);
+}
+
+pub fn wrapper_imports(module: &mut Module, service_name: &str, options: &GenerateActorOptions) {
+    wrapper_core_agent_imports(module, options);
+    candid_import(module, options);
+    // Lenient inputs and principals represented as text are parsed from text at runtime,
+    // and the canisters of the oneway calls are resolved at runtime
+    core_principal_import(
+        module,
+        !(options.input_types || options.principal_text || options.oneway_receipts),
+    );
+    old_bindings_imports(module, service_name, options);
+    type_override_imports(module, options, true);
+    icrc_import(module, options);
+}
+
+fn type_override_imports(
+    module: &mut Module,
+    options: &GenerateActorOptions,
+    with_converters: bool,
+) {
+    // Group the imported names by module, a name is type-only unless it is also a converter
+    let mut imports: BTreeMap<&str, BTreeMap<&str, bool>> = BTreeMap::new();
+    for type_override in &options.type_overrides {
+        let names = imports.entry(type_override.module.as_str()).or_default();
+        names
+            .entry(type_override.type_name.as_str())
+            .or_insert(true);
+        if with_converters {
+            names.insert(type_override.to_candid.as_str(), false);
+            names.insert(type_override.from_candid.as_str(), false);
+        }
+    }
+
+    for (src, names) in imports {
+        module
+            .body
+            .push(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                span: DUMMY_SP,
+                specifiers: names
+                    .into_iter()
+                    .map(|(name, is_type_only)| {
+                        ImportSpecifier::Named(ImportNamedSpecifier {
+                            span: DUMMY_SP,
+                            local: Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty()),
+                            imported: None,
+                            is_type_only,
+                        })
+                    })
+                    .collect(),
+                src: Box::new(Str {
+                    span: DUMMY_SP,
+                    value: src.into(),
+                    raw: None,
+                }),
+                type_only: false,
+                with: None,
+                phase: Default::default(),
+            })));
+    }
 }
 
-pub fn wrapper_imports(module: &mut Module, service_name: &str) {
-    wrapper_core_agent_imports(module);
-    core_principal_import(module);
-    old_bindings_imports(module, service_name);
+fn icrc_import(module: &mut Module, options: &GenerateActorOptions) {
+    if !shares_icrc_types(options) {
+        return;
+    }
+
+    // Import the shared ICRC module as a namespace, e.g. `icrc.Account`
+    module
+        .body
+        .push(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+            span: DUMMY_SP,
+            specifiers: vec![ImportSpecifier::Namespace(ImportStarAsSpecifier {
+                span: DUMMY_SP,
+                local: Ident::new(ICRC_NAMESPACE.into(), DUMMY_SP, SyntaxContext::empty()),
+            })],
+            src: Box::new(Str {
+                span: DUMMY_SP,
+                value: format!("./{}", icrc_module_name(options)).into(),
+                raw: None,
+            }),
+            type_only: true,
+            with: None,
+            phase: Default::default(),
+        })));
 }
 
 fn old_bindings_imports_interface(module: &mut Module, service_name: &str) {
@@ -38,28 +124,48 @@ fn old_bindings_imports_interface(module: &mut Module, service_name: &str) {
         })));
 }
 
-fn old_bindings_imports(module: &mut Module, service_name: &str) {
+fn old_bindings_imports(module: &mut Module, service_name: &str, options: &GenerateActorOptions) {
     let dashed_name = service_name.replace('-', "_");
 
+    let mut specifiers = vec![
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("idlFactory".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: false,
+        }),
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("_SERVICE".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: true,
+        }),
+    ];
+    // The IDL types of the named functions, to call function references
+    if options.func_refs {
+        specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("idlReferenceTypes".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: false,
+        }));
+    }
+    // The interface factories of the named services, to create their actors
+    if options.service_classes {
+        specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("idlFactories".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: false,
+        }));
+    }
+
     // Import _SERVICE
     module
         .body
         .push(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
             span: DUMMY_SP,
-            specifiers: vec![
-                ImportSpecifier::Named(ImportNamedSpecifier {
-                    span: DUMMY_SP,
-                    local: Ident::new("idlFactory".into(), DUMMY_SP, SyntaxContext::empty()),
-                    imported: None,
-                    is_type_only: false,
-                }),
-                ImportSpecifier::Named(ImportNamedSpecifier {
-                    span: DUMMY_SP,
-                    local: Ident::new("_SERVICE".into(), DUMMY_SP, SyntaxContext::empty()),
-                    imported: None,
-                    is_type_only: true,
-                }),
-            ],
+            specifiers,
             src: Box::new(Str {
                 span: DUMMY_SP,
                 value: format!("./declarations/{}.did", dashed_name).into(),
@@ -71,51 +177,100 @@ fn old_bindings_imports(module: &mut Module, service_name: &str) {
         })));
 }
 
-fn core_principal_import(module: &mut Module) {
+fn candid_import(module: &mut Module, options: &GenerateActorOptions) {
+    // The oneway calls and the cache keys are encoded with the IDL of the actor, which types the wrapped methods
+    // and the IDL factories of the certified actors
+    if !(wraps_methods(options) || options.certified_queries) {
+        return;
+    }
+
     module
         .body
         .push(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
             span: DUMMY_SP,
             specifiers: vec![ImportSpecifier::Named(ImportNamedSpecifier {
                 span: DUMMY_SP,
-                local: Ident::new("Principal".into(), DUMMY_SP, SyntaxContext::empty()),
+                local: Ident::new("IDL".into(), DUMMY_SP, SyntaxContext::empty()),
                 imported: None,
                 is_type_only: false,
             })],
             src: Box::new(Str {
                 span: DUMMY_SP,
-                value: "@icp-sdk/core/principal".into(),
+                value: "@icp-sdk/core/candid".into(),
                 raw: None,
             }),
-            type_only: true,
+            type_only: false,
             with: None,
             phase: Default::default(),
         })));
 }
 
-fn interface_core_agent_imports(module: &mut Module) {
-    let import_decl = ImportDecl {
-        span: DUMMY_SP,
-        specifiers: vec![
-            ImportSpecifier::Named(ImportNamedSpecifier {
+fn core_principal_import(module: &mut Module, type_only: bool) {
+    module
+        .body
+        .push(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+            span: DUMMY_SP,
+            specifiers: vec![ImportSpecifier::Named(ImportNamedSpecifier {
                 span: DUMMY_SP,
-                local: Ident::new("HttpAgentOptions".into(), DUMMY_SP, SyntaxContext::empty()),
+                local: Ident::new("Principal".into(), DUMMY_SP, SyntaxContext::empty()),
                 imported: None,
-                is_type_only: true,
-            }),
-            ImportSpecifier::Named(ImportNamedSpecifier {
+                is_type_only: false,
+            })],
+            src: Box::new(Str {
                 span: DUMMY_SP,
-                local: Ident::new("ActorConfig".into(), DUMMY_SP, SyntaxContext::empty()),
-                imported: None,
-                is_type_only: true,
+                value: "@icp-sdk/core/principal".into(),
+                raw: None,
             }),
-            ImportSpecifier::Named(ImportNamedSpecifier {
+            type_only,
+            with: None,
+            phase: Default::default(),
+        })));
+}
+
+fn interface_core_agent_imports(module: &mut Module, options: &GenerateActorOptions) {
+    let mut specifiers = vec![
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("HttpAgentOptions".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: true,
+        }),
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("ActorConfig".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: true,
+        }),
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("Agent".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: true,
+        }),
+    ];
+    // The request ids of the submitted oneway calls, and the methods resolving to their receipt
+    if options.oneway_receipts {
+        for name in ["RequestId", "ActorMethod"] {
+            specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
                 span: DUMMY_SP,
-                local: Ident::new("Agent".into(), DUMMY_SP, SyntaxContext::empty()),
+                local: Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty()),
                 imported: None,
                 is_type_only: true,
-            }),
-        ],
+            }));
+        }
+    }
+    // The options of the calls
+    if options.call_options {
+        specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("CallConfig".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: true,
+        }));
+    }
+    let import_decl = ImportDecl {
+        span: DUMMY_SP,
+        specifiers,
         src: Box::new(Str {
             span: DUMMY_SP,
             value: "@icp-sdk/core/agent".into(),
@@ -130,47 +285,68 @@ fn interface_core_agent_imports(module: &mut Module) {
         .push(ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)));
 }
 
-fn wrapper_core_agent_imports(module: &mut Module) {
-    let import_decl = ImportDecl {
-        span: DUMMY_SP,
-        specifiers: vec![
-            ImportSpecifier::Named(ImportNamedSpecifier {
-                span: DUMMY_SP,
-                local: Ident::new("Actor".into(), DUMMY_SP, SyntaxContext::empty()),
-                imported: None,
-                is_type_only: false,
-            }),
-            ImportSpecifier::Named(ImportNamedSpecifier {
-                span: DUMMY_SP,
-                local: Ident::new("HttpAgent".into(), DUMMY_SP, SyntaxContext::empty()),
-                imported: None,
-                is_type_only: false,
-            }),
-            ImportSpecifier::Named(ImportNamedSpecifier {
-                span: DUMMY_SP,
-                local: Ident::new("HttpAgentOptions".into(), DUMMY_SP, SyntaxContext::empty()),
-                imported: None,
-                is_type_only: true,
-            }),
-            ImportSpecifier::Named(ImportNamedSpecifier {
-                span: DUMMY_SP,
-                local: Ident::new("ActorConfig".into(), DUMMY_SP, SyntaxContext::empty()),
-                imported: None,
-                is_type_only: true,
-            }),
-            ImportSpecifier::Named(ImportNamedSpecifier {
-                span: DUMMY_SP,
-                local: Ident::new("Agent".into(), DUMMY_SP, SyntaxContext::empty()),
-                imported: None,
-                is_type_only: true,
-            }),
-            ImportSpecifier::Named(ImportNamedSpecifier {
+fn wrapper_core_agent_imports(module: &mut Module, options: &GenerateActorOptions) {
+    let mut specifiers = vec![
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("Actor".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: false,
+        }),
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("HttpAgent".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: false,
+        }),
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("HttpAgentOptions".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: true,
+        }),
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("ActorConfig".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: true,
+        }),
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("Agent".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: true,
+        }),
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("ActorSubclass".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: true,
+        }),
+    ];
+    // The request ids of the submitted oneway calls
+    if options.oneway_receipts {
+        specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: Ident::new("RequestId".into(), DUMMY_SP, SyntaxContext::empty()),
+            imported: None,
+            is_type_only: true,
+        }));
+    }
+    // The options of the calls, bound to the methods of the actor or kept by their wrappers
+    if options.call_options || wraps_methods(options) {
+        for name in ["ActorMethod", "CallConfig"] {
+            specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
                 span: DUMMY_SP,
-                local: Ident::new("ActorSubclass".into(), DUMMY_SP, SyntaxContext::empty()),
+                local: Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty()),
                 imported: None,
                 is_type_only: true,
-            }),
-        ],
+            }));
+        }
+    }
+    let import_decl = ImportDecl {
+        span: DUMMY_SP,
+        specifiers,
         src: Box::new(Str {
             span: DUMMY_SP,
             value: "@icp-sdk/core/agent".into(),
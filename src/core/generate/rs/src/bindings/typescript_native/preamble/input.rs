@@ -0,0 +1,313 @@
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+/// Adds the `Input<T>` type, the lenient representation of `T` accepted by the methods:
+///
+/// ```ts
+/// export type Input<T> = T extends bigint ? bigint | number
+///     : T extends Principal ? Principal | string
+///     : T extends Uint8Array ? Uint8Array | number[]
+///     : T extends ArrayBufferView | Function ? T
+///     : T extends ReadonlyMap<infer K, infer V> ? Map<Input<K>, Input<V>>
+///     : T extends object ? { [K in keyof T]: Input<T[K]> }
+///     : T;
+/// ```
+pub fn input_type_utils(module: &mut Module) {
+    let mapped_type = TsType::TsMappedType(TsMappedType {
+        span: DUMMY_SP,
+        readonly: None,
+        type_param: type_param(
+            "K",
+            Some(TsType::TsTypeOperator(TsTypeOperator {
+                span: DUMMY_SP,
+                op: TsTypeOperatorOp::KeyOf,
+                type_ann: Box::new(type_ref("T", vec![])),
+            })),
+        ),
+        name_type: None,
+        optional: None,
+        type_ann: Some(Box::new(input_of(TsType::TsIndexedAccessType(
+            TsIndexedAccessType {
+                span: DUMMY_SP,
+                readonly: false,
+                obj_type: Box::new(type_ref("T", vec![])),
+                index_type: Box::new(type_ref("K", vec![])),
+            },
+        )))),
+    });
+
+    let map_type = conditional(
+        type_ref("ReadonlyMap", vec![infer("K"), infer("V")]),
+        type_ref(
+            "Map",
+            vec![
+                input_of(type_ref("K", vec![])),
+                input_of(type_ref("V", vec![])),
+            ],
+        ),
+        conditional(
+            keyword(TsKeywordTypeKind::TsObjectKeyword),
+            mapped_type,
+            type_ref("T", vec![]),
+        ),
+    );
+
+    let input_type = conditional(
+        keyword(TsKeywordTypeKind::TsBigIntKeyword),
+        union(vec![
+            keyword(TsKeywordTypeKind::TsBigIntKeyword),
+            keyword(TsKeywordTypeKind::TsNumberKeyword),
+        ]),
+        conditional(
+            type_ref("Principal", vec![]),
+            union(vec![
+                type_ref("Principal", vec![]),
+                keyword(TsKeywordTypeKind::TsStringKeyword),
+            ]),
+            conditional(
+                type_ref("Uint8Array", vec![]),
+                union(vec![
+                    type_ref("Uint8Array", vec![]),
+                    TsType::TsArrayType(TsArrayType {
+                        span: DUMMY_SP,
+                        elem_type: Box::new(keyword(TsKeywordTypeKind::TsNumberKeyword)),
+                    }),
+                ]),
+                conditional(
+                    union(vec![
+                        type_ref("ArrayBufferView", vec![]),
+                        type_ref("Function", vec![]),
+                    ]),
+                    type_ref("T", vec![]),
+                    map_type,
+                ),
+            ),
+        ),
+    );
+
+    module
+        .body
+        .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::TsTypeAlias(Box::new(TsTypeAliasDecl {
+                span: DUMMY_SP,
+                declare: false,
+                id: Ident::new("Input".into(), DUMMY_SP, SyntaxContext::empty()),
+                type_params: Some(Box::new(TsTypeParamDecl {
+                    span: DUMMY_SP,
+                    params: vec![type_param("T", None)],
+                })),
+                type_ann: Box::new(input_type),
+            })),
+        })));
+}
+
+/// The name of the function converting the lenient inputs of bigints.
+pub const BIGINT_INPUT: &str = "bigint_input";
+
+/// Adds the function converting the lenient inputs of bigints, in the wrapper.
+/// Numbers beyond `Number.MAX_SAFE_INTEGER` have already lost their precision, so they are rejected:
+///
+/// ```ts
+/// function bigint_input(value: bigint | number): bigint {
+///     if (typeof value === "number" && !Number.isSafeInteger(value)) {
+///         throw new RangeError("The number " + value + " is not a safe integer, pass it as a bigint");
+///     }
+///     return BigInt(value);
+/// }
+/// ```
+pub fn input_value_utils(module: &mut Module) {
+    let value = || Box::new(ident("value"));
+    let is_number = Expr::Bin(BinExpr {
+        span: DUMMY_SP,
+        op: BinaryOp::EqEqEq,
+        left: Box::new(Expr::Unary(UnaryExpr {
+            span: DUMMY_SP,
+            op: UnaryOp::TypeOf,
+            arg: value(),
+        })),
+        right: Box::new(string("number")),
+    });
+    let is_unsafe = Expr::Unary(UnaryExpr {
+        span: DUMMY_SP,
+        op: UnaryOp::Bang,
+        arg: Box::new(call(
+            Expr::Member(MemberExpr {
+                span: DUMMY_SP,
+                obj: Box::new(ident("Number")),
+                prop: MemberProp::Ident(IdentName::new("isSafeInteger".into(), DUMMY_SP)),
+            }),
+            ident("value"),
+        )),
+    });
+    let message = Expr::Bin(BinExpr {
+        span: DUMMY_SP,
+        op: BinaryOp::Add,
+        left: Box::new(Expr::Bin(BinExpr {
+            span: DUMMY_SP,
+            op: BinaryOp::Add,
+            left: Box::new(string("The number ")),
+            right: value(),
+        })),
+        right: Box::new(string(" is not a safe integer, pass it as a bigint")),
+    });
+    let check = Stmt::If(IfStmt {
+        span: DUMMY_SP,
+        test: Box::new(Expr::Bin(BinExpr {
+            span: DUMMY_SP,
+            op: BinaryOp::LogicalAnd,
+            left: Box::new(is_number),
+            right: Box::new(is_unsafe),
+        })),
+        cons: Box::new(Stmt::Block(BlockStmt {
+            span: DUMMY_SP,
+            stmts: vec![Stmt::Throw(ThrowStmt {
+                span: DUMMY_SP,
+                arg: Box::new(Expr::New(NewExpr {
+                    span: DUMMY_SP,
+                    callee: Box::new(ident("RangeError")),
+                    args: Some(vec![ExprOrSpread {
+                        spread: None,
+                        expr: Box::new(message),
+                    }]),
+                    type_args: None,
+                    ctxt: SyntaxContext::empty(),
+                })),
+            })],
+            ctxt: SyntaxContext::empty(),
+        })),
+        alt: None,
+    });
+    let ret = Stmt::Return(ReturnStmt {
+        span: DUMMY_SP,
+        arg: Some(Box::new(call(ident("BigInt"), ident("value")))),
+    });
+
+    let function = FnDecl {
+        ident: Ident::new(BIGINT_INPUT.into(), DUMMY_SP, SyntaxContext::empty()),
+        declare: false,
+        function: Box::new(Function {
+            ctxt: SyntaxContext::empty(),
+            params: vec![Param {
+                span: DUMMY_SP,
+                decorators: vec![],
+                pat: Pat::Ident(BindingIdent {
+                    id: Ident::new("value".into(), DUMMY_SP, SyntaxContext::empty()),
+                    type_ann: Some(Box::new(TsTypeAnn {
+                        span: DUMMY_SP,
+                        type_ann: Box::new(union(vec![
+                            keyword(TsKeywordTypeKind::TsBigIntKeyword),
+                            keyword(TsKeywordTypeKind::TsNumberKeyword),
+                        ])),
+                    })),
+                }),
+            }],
+            decorators: vec![],
+            span: DUMMY_SP,
+            body: Some(BlockStmt {
+                span: DUMMY_SP,
+                stmts: vec![check, ret],
+                ctxt: SyntaxContext::empty(),
+            }),
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: Some(Box::new(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::new(keyword(TsKeywordTypeKind::TsBigIntKeyword)),
+            })),
+        }),
+    };
+    module
+        .body
+        .push(ModuleItem::Stmt(Stmt::Decl(Decl::Fn(function))));
+}
+
+/// Returns `Input<ty>`
+pub fn input_of(ty: TsType) -> TsType {
+    type_ref("Input", vec![ty])
+}
+
+// T extends <extends_type> ? <true_type> : <false_type>
+fn conditional(extends_type: TsType, true_type: TsType, false_type: TsType) -> TsType {
+    TsType::TsConditionalType(TsConditionalType {
+        span: DUMMY_SP,
+        check_type: Box::new(type_ref("T", vec![])),
+        extends_type: Box::new(extends_type),
+        true_type: Box::new(true_type),
+        false_type: Box::new(false_type),
+    })
+}
+
+fn type_ref(name: &str, params: Vec<TsType>) -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::Ident(Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty())),
+        type_params: if params.is_empty() {
+            None
+        } else {
+            Some(Box::new(TsTypeParamInstantiation {
+                span: DUMMY_SP,
+                params: params.into_iter().map(Box::new).collect(),
+            }))
+        },
+    })
+}
+
+fn type_param(name: &str, constraint: Option<TsType>) -> TsTypeParam {
+    TsTypeParam {
+        span: DUMMY_SP,
+        name: Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty()),
+        constraint: constraint.map(Box::new),
+        default: None,
+        is_in: false,
+        is_out: false,
+        is_const: false,
+    }
+}
+
+fn infer(name: &str) -> TsType {
+    TsType::TsInferType(TsInferType {
+        span: DUMMY_SP,
+        type_param: type_param(name, None),
+    })
+}
+
+fn keyword(kind: TsKeywordTypeKind) -> TsType {
+    TsType::TsKeywordType(TsKeywordType {
+        span: DUMMY_SP,
+        kind,
+    })
+}
+
+fn union(types: Vec<TsType>) -> TsType {
+    TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(TsUnionType {
+        span: DUMMY_SP,
+        types: types.into_iter().map(Box::new).collect(),
+    }))
+}
+
+fn ident(name: &str) -> Expr {
+    Expr::Ident(Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty()))
+}
+
+fn string(value: &str) -> Expr {
+    Expr::Lit(Lit::Str(Str {
+        span: DUMMY_SP,
+        value: value.into(),
+        raw: None,
+    }))
+}
+
+fn call(callee: Expr, arg: Expr) -> Expr {
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(callee)),
+        args: vec![ExprOrSpread {
+            spread: None,
+            expr: Box::new(arg),
+        }],
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    })
+}
@@ -1,3 +1,5 @@
 pub mod actor;
 pub mod imports;
+pub mod input;
 pub mod options;
+pub mod service_ref;
@@ -1,6 +1,8 @@
 use swc_core::common::{DUMMY_SP, SyntaxContext};
 use swc_core::ecma::ast::*;
 
+use crate::GenerateActorOptions;
+
 pub fn interface_options_utils(module: &mut Module) {
     let some_type = create_some_type();
     module
@@ -27,7 +29,7 @@ pub fn interface_options_utils(module: &mut Module) {
         })));
 }
 
-pub fn wrapper_options_utils(module: &mut Module) {
+pub fn wrapper_options_utils(module: &mut Module, options: &GenerateActorOptions) {
     let some_function = create_some_function();
     module
         .body
@@ -63,10 +65,13 @@ pub fn wrapper_options_utils(module: &mut Module) {
         .body
         .push(ModuleItem::Stmt(Stmt::Decl(Decl::Fn(candid_none_function))));
 
-    let opt_struct_function = generate_record_opt_undefined();
-    module
-        .body
-        .push(ModuleItem::Stmt(Stmt::Decl(Decl::Fn(opt_struct_function))));
+    // With `optional_fields`, the absent optional fields are omitted instead
+    if !options.optional_fields {
+        let opt_struct_function = generate_record_opt_undefined();
+        module
+            .body
+            .push(ModuleItem::Stmt(Stmt::Decl(Decl::Fn(opt_struct_function))));
+    }
 }
 
 fn generate_unwrap_function() -> FnDecl {
@@ -0,0 +1,334 @@
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use crate::GenerateActorOptions;
+
+/// The name of the type of the service references.
+pub const SERVICE_REF_TYPE: &str = "ServiceRef";
+
+/// The unique symbol keying the brand of the service references, which carries the interface of
+/// the service. It is not exported, so that a reference can only be created by the constructor.
+const SERVICE_PROPERTY: &str = "__service";
+
+/// Adds the `ServiceRef<T>` type, a reference to a service with the interface `T`, and its
+/// constructor:
+///
+/// ```ts
+/// declare const __service: unique symbol;
+/// export type ServiceRef<T> = Principal & {
+///     readonly [__service]: T;
+/// };
+/// export function ServiceRef<T>(principal: Principal): ServiceRef<T> {
+///     return principal as ServiceRef<T>;
+/// }
+/// ```
+///
+/// The reference is the principal of the service (its text if principals are represented as text),
+/// the interface only exists at the type level.
+/// Only the signature of the constructor is declared if `declare` is set, e.g. in the interface file.
+pub fn service_ref_type(module: &mut Module, options: &GenerateActorOptions, declare: bool) {
+    let principal = || {
+        if options.principal_text {
+            TsType::TsKeywordType(TsKeywordType {
+                span: DUMMY_SP,
+                kind: TsKeywordTypeKind::TsStringKeyword,
+            })
+        } else {
+            type_ref("Principal", vec![])
+        }
+    };
+    let service = TsType::TsTypeLit(TsTypeLit {
+        span: DUMMY_SP,
+        members: vec![TsTypeElement::TsPropertySignature(TsPropertySignature {
+            span: DUMMY_SP,
+            readonly: true,
+            key: Box::new(Expr::Ident(ident(SERVICE_PROPERTY))),
+            computed: true,
+            optional: false,
+            type_ann: Some(Box::new(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::new(type_ref("T", vec![])),
+            })),
+        })],
+    });
+
+    // declare const __service: unique symbol;
+    module
+        .body
+        .push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
+            span: DUMMY_SP,
+            kind: VarDeclKind::Const,
+            declare: true,
+            decls: vec![VarDeclarator {
+                span: DUMMY_SP,
+                name: Pat::Ident(BindingIdent {
+                    id: ident(SERVICE_PROPERTY),
+                    type_ann: Some(Box::new(TsTypeAnn {
+                        span: DUMMY_SP,
+                        type_ann: Box::new(TsType::TsTypeOperator(TsTypeOperator {
+                            span: DUMMY_SP,
+                            op: TsTypeOperatorOp::Unique,
+                            type_ann: Box::new(TsType::TsKeywordType(TsKeywordType {
+                                span: DUMMY_SP,
+                                kind: TsKeywordTypeKind::TsSymbolKeyword,
+                            })),
+                        })),
+                    })),
+                }),
+                init: None,
+                definite: false,
+            }],
+            ctxt: SyntaxContext::empty(),
+        })))));
+
+    module
+        .body
+        .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::TsTypeAlias(Box::new(TsTypeAliasDecl {
+                span: DUMMY_SP,
+                declare: false,
+                id: ident(SERVICE_REF_TYPE),
+                type_params: Some(Box::new(TsTypeParamDecl {
+                    span: DUMMY_SP,
+                    params: vec![type_param("T")],
+                })),
+                type_ann: Box::new(TsType::TsUnionOrIntersectionType(
+                    TsUnionOrIntersectionType::TsIntersectionType(TsIntersectionType {
+                        span: DUMMY_SP,
+                        types: vec![Box::new(principal()), Box::new(service)],
+                    }),
+                )),
+            })),
+        })));
+
+    // return principal as ServiceRef<T>;
+    let body = (!declare).then(|| BlockStmt {
+        span: DUMMY_SP,
+        stmts: vec![Stmt::Return(ReturnStmt {
+            span: DUMMY_SP,
+            arg: Some(Box::new(Expr::TsAs(TsAsExpr {
+                span: DUMMY_SP,
+                expr: Box::new(Expr::Ident(ident("principal"))),
+                type_ann: Box::new(service_ref_of(type_ref("T", vec![]))),
+            }))),
+        })],
+        ctxt: SyntaxContext::empty(),
+    });
+
+    module
+        .body
+        .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::Fn(FnDecl {
+                ident: ident(SERVICE_REF_TYPE),
+                declare,
+                function: Box::new(Function {
+                    params: vec![param(ident("principal"), Some(principal()))],
+                    decorators: vec![],
+                    span: DUMMY_SP,
+                    body,
+                    is_generator: false,
+                    is_async: false,
+                    type_params: Some(Box::new(TsTypeParamDecl {
+                        span: DUMMY_SP,
+                        params: vec![type_param("T")],
+                    })),
+                    return_type: Some(Box::new(TsTypeAnn {
+                        span: DUMMY_SP,
+                        type_ann: Box::new(service_ref_of(type_ref("T", vec![]))),
+                    })),
+                    ctxt: SyntaxContext::empty(),
+                }),
+            }),
+        })));
+}
+
+/// Brands a value converted from Candid as a service reference: `value as ServiceRef<T>`
+pub fn service_ref_value(value: Expr, service_ref: TsType) -> Expr {
+    Expr::TsAs(TsAsExpr {
+        span: DUMMY_SP,
+        expr: Box::new(value),
+        type_ann: Box::new(service_ref),
+    })
+}
+
+/// Returns `ServiceRef<service>`
+pub fn service_ref_of(service: TsType) -> TsType {
+    type_ref(SERVICE_REF_TYPE, vec![service])
+}
+
+/// Adds the `fromServiceRef` function, which creates an actor for a received service reference
+/// with the `createActor` function of the service:
+///
+/// ```ts
+/// export function fromServiceRef<T>(ref: ServiceRef<T>, create: (canisterId: string, options?: CreateActorOptions) => T, options?: CreateActorOptions): T {
+///     return create(ref.toString(), options);
+/// }
+/// ```
+///
+/// Only the signature is declared if `declare` is set, e.g. in the interface file.
+pub fn from_service_ref_function(module: &mut Module, declare: bool) {
+    let options_type = || {
+        Some(Box::new(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::new(type_ref("CreateActorOptions", vec![])),
+        }))
+    };
+    let create_type = TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsFnType(TsFnType {
+        span: DUMMY_SP,
+        params: vec![
+            TsFnParam::Ident(BindingIdent {
+                id: ident("canisterId"),
+                type_ann: Some(Box::new(TsTypeAnn {
+                    span: DUMMY_SP,
+                    type_ann: Box::new(TsType::TsKeywordType(TsKeywordType {
+                        span: DUMMY_SP,
+                        kind: TsKeywordTypeKind::TsStringKeyword,
+                    })),
+                })),
+            }),
+            TsFnParam::Ident(BindingIdent {
+                id: optional_ident("options"),
+                type_ann: options_type(),
+            }),
+        ],
+        type_params: None,
+        type_ann: Box::new(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::new(type_ref("T", vec![])),
+        }),
+    }));
+
+    let params = vec![
+        param(ident("ref"), Some(service_ref_of(type_ref("T", vec![])))),
+        param(ident("create"), Some(create_type)),
+        Param {
+            span: DUMMY_SP,
+            decorators: vec![],
+            pat: Pat::Ident(BindingIdent {
+                id: optional_ident("options"),
+                type_ann: options_type(),
+            }),
+        },
+    ];
+
+    // return create(ref.toString(), options);
+    let body = (!declare).then(|| BlockStmt {
+        span: DUMMY_SP,
+        stmts: vec![Stmt::Return(ReturnStmt {
+            span: DUMMY_SP,
+            arg: Some(Box::new(Expr::Call(CallExpr {
+                span: DUMMY_SP,
+                callee: Callee::Expr(Box::new(Expr::Ident(ident("create")))),
+                args: vec![
+                    ExprOrSpread {
+                        spread: None,
+                        expr: Box::new(Expr::Call(CallExpr {
+                            span: DUMMY_SP,
+                            callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+                                span: DUMMY_SP,
+                                obj: Box::new(Expr::Ident(ident("ref"))),
+                                prop: MemberProp::Ident(IdentName::new(
+                                    "toString".into(),
+                                    DUMMY_SP,
+                                )),
+                            }))),
+                            args: vec![],
+                            type_args: None,
+                            ctxt: SyntaxContext::empty(),
+                        })),
+                    },
+                    ExprOrSpread {
+                        spread: None,
+                        expr: Box::new(Expr::Ident(ident("options"))),
+                    },
+                ],
+                type_args: None,
+                ctxt: SyntaxContext::empty(),
+            }))),
+        })],
+        ctxt: SyntaxContext::empty(),
+    });
+
+    module
+        .body
+        .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::Fn(FnDecl {
+                ident: ident("fromServiceRef"),
+                declare,
+                function: Box::new(Function {
+                    params,
+                    decorators: vec![],
+                    span: DUMMY_SP,
+                    body,
+                    is_generator: false,
+                    is_async: false,
+                    type_params: Some(Box::new(TsTypeParamDecl {
+                        span: DUMMY_SP,
+                        params: vec![type_param("T")],
+                    })),
+                    return_type: Some(Box::new(TsTypeAnn {
+                        span: DUMMY_SP,
+                        type_ann: Box::new(type_ref("T", vec![])),
+                    })),
+                    ctxt: SyntaxContext::empty(),
+                }),
+            }),
+        })));
+}
+
+fn param(id: Ident, ty: Option<TsType>) -> Param {
+    Param {
+        span: DUMMY_SP,
+        decorators: vec![],
+        pat: Pat::Ident(BindingIdent {
+            id,
+            type_ann: ty.map(|ty| {
+                Box::new(TsTypeAnn {
+                    span: DUMMY_SP,
+                    type_ann: Box::new(ty),
+                })
+            }),
+        }),
+    }
+}
+
+fn ident(name: &str) -> Ident {
+    Ident::new(name.into(), DUMMY_SP, SyntaxContext::empty())
+}
+
+fn optional_ident(name: &str) -> Ident {
+    Ident {
+        optional: true,
+        ..ident(name)
+    }
+}
+
+fn type_ref(name: &str, params: Vec<TsType>) -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::Ident(ident(name)),
+        type_params: if params.is_empty() {
+            None
+        } else {
+            Some(Box::new(TsTypeParamInstantiation {
+                span: DUMMY_SP,
+                params: params.into_iter().map(Box::new).collect(),
+            }))
+        },
+    })
+}
+
+fn type_param(name: &str) -> TsTypeParam {
+    TsTypeParam {
+        span: DUMMY_SP,
+        name: ident(name),
+        constraint: None,
+        default: None,
+        is_in: false,
+        is_out: false,
+        is_const: false,
+    }
+}
@@ -0,0 +1,302 @@
+use super::utils::get_ident_guarded;
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use super::runtime::Runtime;
+use crate::GenerateActorOptions;
+
+/// The name of the method of the actors invalidating the cached responses.
+pub const INVALIDATE: &str = "invalidate";
+
+/// The function wrapping the actor with the query cache, in the wrapper.
+const WITH_QUERY_CACHE: &str = "with_query_cache";
+
+/// The query cache of the actors.
+pub const QUERY_CACHE: Runtime = Runtime {
+    enabled: |options| options.query_cache,
+    types: r#"/**
+ * The cache of the responses of the query methods, see the `queryCache` option of `createActor`.
+ */
+export interface QueryCacheOptions {
+    /**
+     * The time to live of the cached responses in milliseconds. Defaults to 60000.
+     */
+    ttl?: number;
+    /**
+     * The maximum number of cached responses, the oldest ones are evicted first. Defaults to 1000.
+     */
+    maxSize?: number;
+}
+"#,
+    classes: &[],
+    implementation: r#"interface CachedResponse {
+    methodName: string;
+    expiresAt: number;
+    response: Promise<ReturnType<typeof IDL.encode>>;
+}
+const QUERY_CACHE = Symbol("query_cache");
+function encode_response(func: IDL.FuncClass, result: unknown): ReturnType<typeof IDL.encode> {
+    const values = func.retTypes.length === 0 ? [] : func.retTypes.length === 1 ? [
+        result
+    ] : result as unknown[];
+    return IDL.encode(func.retTypes, values);
+}
+function decode_response(func: IDL.FuncClass, response: ReturnType<typeof IDL.encode>): unknown {
+    const values = IDL.decode(func.retTypes, response);
+    return values.length === 0 ? undefined : values.length === 1 ? values[0] : values;
+}
+function with_query_cache<T>(actor: ActorSubclass<T>, options: QueryCacheOptions | undefined): ActorSubclass<T> {
+    if (options === undefined) {
+        return actor;
+    }
+    const { ttl = 60000, maxSize = 1000 } = options;
+    const cache = new Map<string, CachedResponse>();
+    const cachedActor = wrap_methods(actor, (methodName, func, call, callOptions)=>{
+        const mode = call_mode(func);
+        // The calls with options may be made with another agent, whose identity may get other responses
+        if ((mode !== "query" && mode !== "composite_query") || callOptions !== undefined) {
+            return call;
+        }
+        return async (...args)=>{
+            const arg = IDL.encode(func.argTypes, args);
+            const key = `${methodName}:${Array.from(arg, (byte)=>byte.toString(16).padStart(2, "0")).join("")}`;
+            const now = Date.now();
+            const cached = cache.get(key);
+            if (cached !== undefined) {
+                if (cached.expiresAt > now) {
+                    return decode_response(func, await cached.response);
+                }
+                cache.delete(key);
+            }
+            // The responses are cached encoded, so that each call gets its own copy of the values
+            const response = call(...args).then((result)=>encode_response(func, result));
+            cache.set(key, {
+                methodName,
+                expiresAt: now + ttl,
+                response
+            });
+            // The entries are kept in insertion order, so the expired and the oldest ones come first
+            for (const [oldest, entry] of cache){
+                if (cache.size <= maxSize && entry.expiresAt > now) {
+                    break;
+                }
+                cache.delete(oldest);
+            }
+            // The failed calls are not cached
+            response.catch(()=>{
+                if (cache.get(key)?.response === response) {
+                    cache.delete(key);
+                }
+            });
+            return decode_response(func, await response);
+        };
+    });
+    return new Proxy(cachedActor, {
+        get (target, property, receiver) {
+            return property === QUERY_CACHE ? cache : Reflect.get(target, property, receiver);
+        }
+    });
+}
+function invalidate_query_cache(actor: ActorSubclass, methodName?: string): void {
+    const cache: Map<string, CachedResponse> | undefined = Reflect.get(actor, QUERY_CACHE);
+    if (cache === undefined) {
+        return;
+    }
+    for (const [key, cached] of cache){
+        if (methodName === undefined || cached.methodName === methodName) {
+            cache.delete(key);
+        }
+    }
+}
+"#,
+};
+
+// methodName?: string
+fn method_name_param() -> BindingIdent {
+    BindingIdent {
+        id: Ident {
+            optional: true,
+            ..Ident::new("methodName".into(), DUMMY_SP, SyntaxContext::empty())
+        },
+        type_ann: Some(Box::new(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::new(TsType::TsKeywordType(TsKeywordType {
+                span: DUMMY_SP,
+                kind: TsKeywordTypeKind::TsStringKeyword,
+            })),
+        })),
+    }
+}
+
+// void
+fn void_type_ann() -> Box<TsTypeAnn> {
+    Box::new(TsTypeAnn {
+        span: DUMMY_SP,
+        type_ann: Box::new(TsType::TsKeywordType(TsKeywordType {
+            span: DUMMY_SP,
+            kind: TsKeywordTypeKind::TsVoidKeyword,
+        })),
+    })
+}
+
+/// Returns the `invalidate` method of a service interface:
+///
+/// ```ts
+/// invalidate(methodName?: string): void;
+/// ```
+pub fn invalidate_signature() -> TsTypeElement {
+    TsTypeElement::TsMethodSignature(TsMethodSignature {
+        span: DUMMY_SP,
+        key: Box::new(Expr::Ident(get_ident_guarded(INVALIDATE))),
+        computed: false,
+        optional: false,
+        params: vec![TsFnParam::Ident(method_name_param())],
+        type_ann: Some(void_type_ann()),
+        type_params: None,
+    })
+}
+
+/// Returns the `invalidate` method of an actor class, which removes the cached responses
+/// of a query method, or of all the query methods:
+///
+/// ```ts
+/// invalidate(methodName?: string): void {
+///     invalidate_query_cache(this.actor, methodName);
+/// }
+/// ```
+pub fn invalidate_method() -> ClassMember {
+    let invalidate_call = Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(Expr::Ident(Ident::new(
+            "invalidate_query_cache".into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )))),
+        args: vec![
+            Expr::Member(MemberExpr {
+                span: DUMMY_SP,
+                obj: Box::new(Expr::This(ThisExpr { span: DUMMY_SP })),
+                prop: MemberProp::Ident(IdentName::new("actor".into(), DUMMY_SP)),
+            }),
+            Expr::Ident(Ident::new(
+                "methodName".into(),
+                DUMMY_SP,
+                SyntaxContext::empty(),
+            )),
+        ]
+        .into_iter()
+        .map(|expr| ExprOrSpread {
+            spread: None,
+            expr: Box::new(expr),
+        })
+        .collect(),
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    });
+
+    ClassMember::Method(ClassMethod {
+        span: DUMMY_SP,
+        key: PropName::Ident(IdentName::new(INVALIDATE.into(), DUMMY_SP)),
+        function: Box::new(Function {
+            params: vec![Param {
+                span: DUMMY_SP,
+                decorators: vec![],
+                pat: Pat::Ident(method_name_param()),
+            }],
+            decorators: vec![],
+            span: DUMMY_SP,
+            body: Some(BlockStmt {
+                span: DUMMY_SP,
+                stmts: vec![Stmt::Expr(ExprStmt {
+                    span: DUMMY_SP,
+                    expr: Box::new(invalidate_call),
+                })],
+                ctxt: SyntaxContext::empty(),
+            }),
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: Some(void_type_ann()),
+            ctxt: SyntaxContext::empty(),
+        }),
+        kind: MethodKind::Method,
+        is_static: false,
+        accessibility: None,
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+    })
+}
+
+/// Returns the `queryCache` property of the `CreateActorOptions` interface, if enabled:
+///
+/// ```ts
+/// queryCache?: QueryCacheOptions;
+/// ```
+pub fn query_cache_property(options: &GenerateActorOptions) -> Option<TsTypeElement> {
+    if !options.query_cache {
+        return None;
+    }
+    Some(TsTypeElement::TsPropertySignature(TsPropertySignature {
+        span: DUMMY_SP,
+        readonly: false,
+        key: Box::new(Expr::Ident(Ident::new(
+            "queryCache".into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        ))),
+        computed: false,
+        optional: true,
+        type_ann: Some(Box::new(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+                span: DUMMY_SP,
+                type_name: TsEntityName::Ident(Ident::new(
+                    "QueryCacheOptions".into(),
+                    DUMMY_SP,
+                    SyntaxContext::empty(),
+                )),
+                type_params: None,
+            })),
+        })),
+    }))
+}
+
+/// Wraps the actor created by `createActor` with the configured query cache, if enabled:
+///
+/// ```ts
+/// with_query_cache(actor, options.queryCache)
+/// ```
+pub fn with_query_cache(options: &GenerateActorOptions, actor: Expr) -> Expr {
+    if !options.query_cache {
+        return actor;
+    }
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(Expr::Ident(Ident::new(
+            WITH_QUERY_CACHE.into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )))),
+        args: vec![
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(actor),
+            },
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(Expr::Member(MemberExpr {
+                    span: DUMMY_SP,
+                    obj: Box::new(Expr::Ident(Ident::new(
+                        "options".into(),
+                        DUMMY_SP,
+                        SyntaxContext::empty(),
+                    ))),
+                    prop: MemberProp::Ident(IdentName::new("queryCache".into(), DUMMY_SP)),
+                })),
+            },
+        ],
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    })
+}
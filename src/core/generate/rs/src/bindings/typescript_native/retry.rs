@@ -0,0 +1,220 @@
+use candid::types::{Type, TypeEnv, TypeInner};
+use candid_parser::syntax::IDLType;
+use swc_core::common::{DUMMY_SP, SyntaxContext};
+use swc_core::ecma::ast::*;
+
+use super::method_modes::method_function;
+use super::runtime::Runtime;
+use crate::GenerateActorOptions;
+
+/// The function wrapping the actor with the retry policy, in the wrapper.
+const WITH_RETRY_POLICY: &str = "with_retry_policy";
+
+/// The doc annotation marking an update method as idempotent.
+const IDEMPOTENT_TAG: &str = "@idempotent";
+
+/// The retry policy of the calls.
+pub const RETRY_POLICY: Runtime = Runtime {
+    enabled: |options| options.retry_policy,
+    types: r#"/**
+ * The retry policy of the calls of an actor, see the `retryPolicy` option of `createActor`.
+ * Only the query methods and the update methods marked as idempotent are retried.
+ */
+export interface RetryPolicy {
+    /**
+     * The maximum number of attempts of a call, including the first one. Defaults to 3.
+     */
+    attempts?: number;
+    /**
+     * The delay before the first retry in milliseconds, doubled at each retry. Defaults to 100.
+     */
+    backoff?: number;
+    /**
+     * The random variation of the delays, e.g. 0.2 for ±20%. Defaults to 0.2.
+     */
+    jitter?: number;
+    /**
+     * Whether a call failing with the error can be retried.
+     * Defaults to the network failures: the rejections of `fetch`, and the HTTP errors of the replica
+     * with a 5xx or 429 status.
+     */
+    retryable?: (error: unknown) => boolean;
+}
+"#,
+    classes: &[],
+    implementation: r#"function is_transient(error: unknown): boolean {
+    for(let cause = error, depth = 0; typeof cause === "object" && cause !== null && depth < 5; depth++){
+        // The rejections of `fetch`, e.g. if the connection was lost
+        if (cause instanceof TypeError) {
+            return /fetch|network|load failed/i.test(cause.message);
+        }
+        // The HTTP errors of the replica, e.g. `AgentHTTPResponseError`, if it is unavailable or overloaded
+        const { response, status, cause: next, code, error: inner } = cause as Record<string, unknown>;
+        const httpStatus = typeof response === "object" && response !== null ? (response as Record<string, unknown>).status : status;
+        if (typeof httpStatus === "number") {
+            return httpStatus >= 500 || httpStatus === 429;
+        }
+        cause = next ?? code ?? inner;
+    }
+    return false;
+}
+function with_retry_policy<T>(actor: ActorSubclass<T>, policy: RetryPolicy | undefined, idempotentMethods: string[]): ActorSubclass<T> {
+    if (policy === undefined) {
+        return actor;
+    }
+    const { attempts = 3, backoff = 100, jitter = 0.2, retryable = is_transient } = policy;
+    return wrap_methods(actor, (methodName, func, call)=>{
+        const mode = call_mode(func);
+        if (mode !== "query" && mode !== "composite_query" && !(mode === "update" && idempotentMethods.includes(methodName))) {
+            return call;
+        }
+        return async (...args)=>{
+            for(let attempt = 1;; attempt++){
+                try {
+                    return await call(...args);
+                } catch (error) {
+                    if (attempt >= attempts || !retryable(error)) {
+                        throw error;
+                    }
+                    const delay = backoff * 2 ** (attempt - 1) * (1 + jitter * (2 * Math.random() - 1));
+                    await new Promise((resolve)=>setTimeout(resolve, delay));
+                }
+            }
+        };
+    });
+}
+"#,
+};
+
+/// Returns the update methods of an actor that can be retried, because they are listed
+/// in the idempotent methods of the options or annotated with `@idempotent` in their docs.
+pub fn idempotent_methods(
+    env: &TypeEnv,
+    options: &GenerateActorOptions,
+    actor_type: &Type,
+    syntax: Option<&IDLType>,
+) -> Vec<String> {
+    if !options.retry_policy {
+        return vec![];
+    }
+    let serv = match actor_type.as_ref() {
+        TypeInner::Service(serv) => serv,
+        TypeInner::Var(id) => match env.rec_find_type(id).map(|ty| ty.as_ref()) {
+            Ok(TypeInner::Service(serv)) => serv,
+            _ => return vec![],
+        },
+        TypeInner::Class(_, t) => {
+            let syntax = match syntax {
+                Some(IDLType::ClassT(_, syntax_t)) => Some(syntax_t.as_ref()),
+                _ => None,
+            };
+            return idempotent_methods(env, options, t, syntax);
+        }
+        _ => return vec![],
+    };
+    let syntax_serv = match syntax {
+        Some(IDLType::ServT(syntax_serv)) => Some(syntax_serv),
+        _ => None,
+    };
+    serv.iter()
+        .filter(|(method_id, method_ty)| {
+            let is_update = method_function(env, method_ty).is_some_and(|f| f.modes.is_empty());
+            let annotated = syntax_serv
+                .and_then(|bindings| bindings.iter().find(|b| &b.id == method_id))
+                .is_some_and(|b| b.docs.iter().any(|line| line.trim() == IDEMPOTENT_TAG));
+            is_update && (annotated || options.idempotent_methods.contains(method_id))
+        })
+        .map(|(method_id, _)| method_id.clone())
+        .collect()
+}
+
+/// Returns the `retryPolicy` property of the `CreateActorOptions` interface, if enabled:
+///
+/// ```ts
+/// retryPolicy?: RetryPolicy;
+/// ```
+pub fn retry_policy_property(options: &GenerateActorOptions) -> Option<TsTypeElement> {
+    if !options.retry_policy {
+        return None;
+    }
+    Some(TsTypeElement::TsPropertySignature(TsPropertySignature {
+        span: DUMMY_SP,
+        readonly: false,
+        key: Box::new(Expr::Ident(Ident::new(
+            "retryPolicy".into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        ))),
+        computed: false,
+        optional: true,
+        type_ann: Some(Box::new(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::new(TsType::TsTypeRef(TsTypeRef {
+                span: DUMMY_SP,
+                type_name: TsEntityName::Ident(Ident::new(
+                    "RetryPolicy".into(),
+                    DUMMY_SP,
+                    SyntaxContext::empty(),
+                )),
+                type_params: None,
+            })),
+        })),
+    }))
+}
+
+/// Wraps the actor created by `createActor` with the configured retry policy, if enabled:
+///
+/// ```ts
+/// with_retry_policy(actor, options.retryPolicy, ["set_greeting"])
+/// ```
+pub fn with_retry_policy(
+    options: &GenerateActorOptions,
+    actor: Expr,
+    idempotent: &[String],
+) -> Expr {
+    if !options.retry_policy {
+        return actor;
+    }
+    let retry_policy = Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(Expr::Ident(Ident::new(
+            "options".into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        ))),
+        prop: MemberProp::Ident(IdentName::new("retryPolicy".into(), DUMMY_SP)),
+    });
+    let idempotent = Expr::Array(ArrayLit {
+        span: DUMMY_SP,
+        elems: idempotent
+            .iter()
+            .map(|method_id| {
+                Some(ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(Expr::Lit(Lit::Str(Str {
+                        span: DUMMY_SP,
+                        value: method_id.as_str().into(),
+                        raw: None,
+                    }))),
+                })
+            })
+            .collect(),
+    });
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(Expr::Ident(Ident::new(
+            WITH_RETRY_POLICY.into(),
+            DUMMY_SP,
+            SyntaxContext::empty(),
+        )))),
+        args: [actor, retry_policy, idempotent]
+            .into_iter()
+            .map(|expr| ExprOrSpread {
+                spread: None,
+                expr: Box::new(expr),
+            })
+            .collect(),
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    })
+}
@@ -0,0 +1,129 @@
+use super::call_options::CALL_OPTIONS;
+use super::canister_errors::CANISTER_ERRORS;
+use super::certified::CERTIFIED_QUERIES;
+use super::interceptors::INTERCEPTORS;
+use super::method_wrappers::WRAP_METHODS;
+use super::oneway::ONEWAY_RECEIPTS;
+use super::query_cache::QUERY_CACHE;
+use super::retry::RETRY_POLICY;
+use crate::GenerateActorOptions;
+
+/// The TypeScript code of a feature of the generated actors, appended to the generated files.
+/// The exported types and classes are written once, and declared in both the interface file and the wrapper.
+pub struct Runtime {
+    /// Whether the feature is enabled by the options.
+    pub enabled: fn(&GenerateActorOptions) -> bool,
+    /// The exported types, e.g. the options of the feature.
+    pub types: &'static str,
+    /// The exported classes, e.g. the errors of the feature.
+    pub classes: &'static [RuntimeClass],
+    /// The implementation of the feature, only appended to the wrapper.
+    pub implementation: &'static str,
+}
+
+/// A class exported by a feature, whose constructor sets its `readonly` fields.
+/// A class without fields only overrides the `name` of its parent class.
+pub struct RuntimeClass {
+    /// The doc comment of the class.
+    pub docs: &'static str,
+    pub name: &'static str,
+    pub extends: &'static str,
+    /// The names and the types of the fields.
+    pub fields: &'static [(&'static str, &'static str)],
+    /// The message of the error, a template literal over the fields.
+    pub message: &'static str,
+}
+
+/// The features, in the order of their code in the generated files.
+const RUNTIMES: &[&Runtime] = &[
+    &CANISTER_ERRORS,
+    &WRAP_METHODS,
+    &INTERCEPTORS,
+    &RETRY_POLICY,
+    &QUERY_CACHE,
+    &CERTIFIED_QUERIES,
+    &CALL_OPTIONS,
+    &ONEWAY_RECEIPTS,
+];
+
+impl RuntimeClass {
+    /// Declares the class, in the interface file:
+    ///
+    /// ```ts
+    /// export declare class OnewaySubmissionError extends Error {
+    ///     readonly methodName: string;
+    ///     constructor(methodName: string);
+    /// }
+    /// ```
+    fn declaration(&self) -> String {
+        let mut declaration = format!(
+            "{}export declare class {} extends {} {{\n",
+            self.docs, self.name, self.extends
+        );
+        if !self.fields.is_empty() {
+            for (name, ty) in self.fields {
+                declaration += &format!("    readonly {}: {};\n", name, ty);
+            }
+            declaration += &format!("    constructor({});\n", self.params(""));
+        }
+        declaration + "}\n"
+    }
+
+    /// Defines the class, in the wrapper:
+    ///
+    /// ```ts
+    /// export class OnewaySubmissionError extends Error {
+    ///     constructor(readonly methodName: string){
+    ///         super(`The oneway call to ${methodName} could not be submitted`);
+    ///         this.name = "OnewaySubmissionError";
+    ///     }
+    /// }
+    /// ```
+    fn definition(&self) -> String {
+        let mut definition = format!(
+            "{}export class {} extends {} {{\n",
+            self.docs, self.name, self.extends
+        );
+        if self.fields.is_empty() {
+            definition += &format!("    override name = \"{}\";\n", self.name);
+        } else {
+            definition += &format!("    constructor({}){{\n", self.params("readonly "));
+            definition += &format!("        super(`{}`);\n", self.message);
+            definition += &format!("        this.name = \"{}\";\n", self.name);
+            definition += "    }\n";
+        }
+        definition + "}\n"
+    }
+
+    fn params(&self, modifier: &str) -> String {
+        self.fields
+            .iter()
+            .map(|(name, ty)| format!("{}{}: {}", modifier, name, ty))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Returns the types and the declared classes of the enabled features, for the interface file.
+pub fn runtime_declarations(options: &GenerateActorOptions) -> String {
+    RUNTIMES
+        .iter()
+        .filter(|runtime| (runtime.enabled)(options))
+        .map(|runtime| {
+            let classes = runtime.classes.iter().map(RuntimeClass::declaration);
+            runtime.types.to_string() + &classes.collect::<String>()
+        })
+        .collect()
+}
+
+/// Returns the types, the classes and the implementation of the enabled features, for the wrapper.
+pub fn runtime_implementations(options: &GenerateActorOptions) -> String {
+    RUNTIMES
+        .iter()
+        .filter(|runtime| (runtime.enabled)(options))
+        .map(|runtime| {
+            let classes = runtime.classes.iter().map(RuntimeClass::definition);
+            runtime.types.to_string() + &classes.collect::<String>() + runtime.implementation
+        })
+        .collect()
+}
@@ -0,0 +1,47 @@
+use super::utils::{KEYWORDS, contains_unicode_characters};
+use candid::types::TypeEnv;
+
+use crate::GenerateActorOptions;
+
+/// Returns whether `name` can be imported as is, i.e. whether it is an identifier and not a keyword.
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !contains_unicode_characters(name)
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && !KEYWORDS.contains(&name)
+}
+
+/// Checks that the type overrides name types of the Candid file, and that their type names,
+/// converter functions and modules can be imported.
+pub fn check_type_overrides(env: &TypeEnv, options: &GenerateActorOptions) -> Result<(), String> {
+    for type_override in &options.type_overrides {
+        let name = &type_override.name;
+        if env.find_type(name).is_err() {
+            return Err(format!(
+                "Unknown type `{}` in the type overrides. Only the named types of the Candid file can be overridden.",
+                name
+            ));
+        }
+        for identifier in [
+            &type_override.type_name,
+            &type_override.to_candid,
+            &type_override.from_candid,
+        ] {
+            if !is_identifier(identifier) {
+                return Err(format!(
+                    "Invalid name `{}` in the type override of `{}`, expected an identifier.",
+                    identifier, name
+                ));
+            }
+        }
+        let module = &type_override.module;
+        if module.is_empty() || module.chars().any(char::is_control) {
+            return Err(format!(
+                "Invalid module `{}` in the type override of `{}`.",
+                module.escape_debug(),
+                name
+            ));
+        }
+    }
+    Ok(())
+}
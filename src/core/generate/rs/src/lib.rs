@@ -28,12 +28,124 @@ pub struct GenerateDeclarationsOptions {
     pub typescript: bool,
 }
 
+#[derive(Tsify, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct GenerateTypeOverride {
+    /// The name of the Candid type to override.
+    pub name: String,
+    /// The name of the TypeScript type exported by `module`.
+    pub type_name: String,
+    /// The module to import the type and the converter functions from.
+    pub module: String,
+    /// The name of the function converting the TypeScript type to the Candid value.
+    pub to_candid: String,
+    /// The name of the function converting the Candid value to the TypeScript type.
+    pub from_candid: String,
+}
+
+#[derive(Tsify, Deserialize, Default)]
+#[tsify(from_wasm_abi)]
+pub struct GenerateActorOptions {
+    /// Omits the absent optional record fields from the results, and sends the missing (`undefined`)
+    /// or `null` optional fields as absent, keeping the falsy values like `0`, `""` or `false`.
+    #[serde(default)]
+    pub optional_fields: bool,
+    /// Represents every association list (`vec record { K; V }`) as a `Map<K, V>`.
+    #[serde(default)]
+    pub map_types: bool,
+    /// Names of the association list types to represent as a `Map<K, V>`.
+    #[serde(default)]
+    pub map_type_names: Vec<String>,
+    /// Emits camelCase names for record fields and methods.
+    #[serde(default)]
+    pub camel_case: bool,
+    /// Named Candid types to represent with user-defined types and converter functions.
+    #[serde(default)]
+    pub type_overrides: Vec<GenerateTypeOverride>,
+    /// Represents the well-known ICRC standard types with the types of a shared `icrc.ts` module.
+    #[serde(default)]
+    pub icrc_types: bool,
+    /// Accepts lenient `Input<T>` types for the method arguments.
+    #[serde(default)]
+    pub input_types: bool,
+    /// Returns multiple return values as an object keyed by their Candid names.
+    #[serde(default)]
+    pub named_returns: bool,
+    /// Takes the arguments of methods with more than one argument as a single object keyed by their names.
+    #[serde(default)]
+    pub named_args: bool,
+    /// Represents aliases of primitive types as branded types, with a constructor of the same name.
+    #[serde(default)]
+    pub branded_types: bool,
+    /// Emits readonly properties, tuples, arrays (`ReadonlyArray`) and maps (`ReadonlyMap`).
+    #[serde(default)]
+    pub readonly_types: bool,
+    /// Freezes the objects and arrays returned by the methods with `Object.freeze`.
+    #[serde(default)]
+    pub freeze_results: bool,
+    /// Known names of hashed record field labels, used instead of the `_<hash>_` keys.
+    #[serde(default)]
+    pub label_names: Vec<String>,
+    /// Represents principals as their text instead of `Principal` objects.
+    #[serde(default)]
+    pub principal_text: bool,
+    /// Represents service references as `ServiceRef<T>`, typed with the interface `T` of the service.
+    #[serde(default)]
+    pub service_refs: bool,
+    /// Generates a callable constructor for each named function type, to call function references.
+    #[serde(default)]
+    pub func_refs: bool,
+    /// Generates a wrapper class and a `create<Name>Actor` function for each named service type.
+    #[serde(default)]
+    pub service_classes: bool,
+    /// Exposes the call mode of the methods, as JSDoc tags and a static `methodModes` table on the actor classes.
+    #[serde(default)]
+    pub method_modes: bool,
+    /// Submits the oneway methods without waiting, returning a `OnewayReceipt` with the request id.
+    #[serde(default)]
+    pub oneway_receipts: bool,
+    /// Adds a `certified` property to the actors, which calls the query methods as update calls.
+    #[serde(default)]
+    pub certified_queries: bool,
+    /// Adds a `withOptions` method to the actors, whose calls are made with the given call options.
+    #[serde(default)]
+    pub call_options: bool,
+    /// Adds an `...OrThrow` variant of the methods returning a `variant { Ok : T; Err : E }`,
+    /// which returns `T` or throws the `Err` payload in a `<Service>Error`.
+    #[serde(default)]
+    pub or_throw_methods: bool,
+    /// Translates the reject errors of the calls into `CanisterRejectError` and `CanisterTrapError`.
+    #[serde(default)]
+    pub canister_errors: bool,
+    /// Adds an `interceptors` option to `createActor`, whose hooks are invoked around the calls.
+    #[serde(default)]
+    pub interceptors: bool,
+    /// Adds a `retryPolicy` option to `createActor`, which retries the query methods and the idempotent update methods.
+    #[serde(default)]
+    pub retry_policy: bool,
+    /// The update methods retried by the retry policy, in addition to the methods annotated with `@idempotent`.
+    #[serde(default)]
+    pub idempotent_methods: Vec<String>,
+    /// Adds a `queryCache` option to `createActor`, which caches the responses of the query methods,
+    /// and an `invalidate` method to the actors.
+    #[serde(default)]
+    pub query_cache: bool,
+}
+
+impl GenerateActorOptions {
+    pub fn type_override(&self, name: &str) -> Option<&GenerateTypeOverride> {
+        self.type_overrides.iter().find(|o| o.name == name)
+    }
+}
+
 #[derive(Tsify, Deserialize)]
 #[tsify(from_wasm_abi)]
 pub struct GenerateOptions {
     pub did_file_path: String,
     pub service_name: String,
     pub declarations: GenerateDeclarationsOptions,
+    #[serde(default)]
+    pub actor: GenerateActorOptions,
 }
 
 #[wasm_bindgen(getter_with_clone)]
@@ -43,6 +155,8 @@ pub struct GenerateResult {
     pub declarations_typescript: String,
     pub interface_ts: String,
     pub service_ts: String,
+    pub icrc_ts: String,
+    pub icrc_module: String,
 }
 
 #[wasm_bindgen]
@@ -50,26 +164,73 @@ pub fn generate(options: GenerateOptions) -> Result<GenerateResult, JsError> {
     let input_path = PathBuf::from(options.did_file_path);
     let (env, actor, prog) = parser::check_file(input_path.as_path()).map_err(JsError::from)?;
 
-    let declarations_js = javascript::compile(&env, &actor, options.declarations.root_exports);
-    let declarations_ts =
-        typescript::compile(&env, &actor, &prog, options.declarations.root_exports);
+    // The IDL types of the named services and functions are used to call function references
+    // and to create the actors of the named services
+    let reference_types = options.actor.func_refs || options.actor.service_classes;
+    let declarations_js = javascript::compile(
+        &env,
+        &actor,
+        options.declarations.root_exports,
+        reference_types,
+    );
+    let declarations_ts = typescript::compile(
+        &env,
+        &actor,
+        &prog,
+        options.declarations.root_exports,
+        reference_types,
+    );
 
     let declarations_typescript = if options.declarations.typescript {
-        javascript::compile_typescript(&env, &actor, &prog, options.declarations.root_exports)
+        javascript::compile_typescript(
+            &env,
+            &actor,
+            &prog,
+            options.declarations.root_exports,
+            reference_types,
+        )
     } else {
         String::new()
     };
 
+    typescript_native::type_overrides::check_type_overrides(&env, &options.actor)
+        .map_err(|e| JsError::new(&e))?;
+    typescript_native::icrc::check_service_name(&options.service_name, &options.actor)
+        .map_err(|e| JsError::new(&e))?;
+
+    if options.actor.camel_case {
+        typescript_native::naming::check_camel_case_collisions(&env, &actor, &options.actor)
+            .map_err(|e| JsError::new(&e))?;
+    }
+    typescript_native::naming::check_class_members(&env, &actor, &options.actor)
+        .map_err(|e| JsError::new(&e))?;
+
     let interface_ts = typescript_native::compile::compile(
         &env,
         &actor,
         &options.service_name,
         "interface",
         &prog,
+        &options.actor,
+    );
+
+    let service_ts = typescript_native::compile::compile(
+        &env,
+        &actor,
+        &options.service_name,
+        "wrapper",
+        &prog,
+        &options.actor,
     );
 
-    let service_ts =
-        typescript_native::compile::compile(&env, &actor, &options.service_name, "wrapper", &prog);
+    let (icrc_ts, icrc_module) = if typescript_native::icrc::shares_icrc_types(&options.actor) {
+        (
+            typescript_native::icrc::compile_icrc(&options.actor),
+            typescript_native::icrc::icrc_module_name(&options.actor),
+        )
+    } else {
+        (String::new(), String::new())
+    };
 
     Ok(GenerateResult {
         declarations_js,
@@ -77,5 +238,7 @@ pub fn generate(options: GenerateOptions) -> Result<GenerateResult, JsError> {
         declarations_typescript,
         interface_ts,
         service_ts,
+        icrc_ts,
+        icrc_module,
     })
 }
@@ -263,7 +263,16 @@ pub fn check_file(file: &Path) -> Result<(TypeEnv, Option<Type>, IDLMergedProg)>
         let include_service = visited.get(&path).unwrap();
         merged_prog.merge(*include_service, name, prog)?;
     }
+    check_merged_prog(merged_prog)
+}
+
+/// Checks a Candid source without imports, e.g. one embedded in the generator.
+pub fn check_str(name: &str, source: &str) -> Result<(TypeEnv, Option<Type>, IDLMergedProg)> {
+    let prog = pretty_parse::<IDLProg>(name, source)?;
+    check_merged_prog(IDLMergedProg::new(prog))
+}
 
+fn check_merged_prog(merged_prog: IDLMergedProg) -> Result<(TypeEnv, Option<Type>, IDLMergedProg)> {
     let mut te = TypeEnv::new();
     let mut env = Env {
         te: &mut te,
@@ -0,0 +1,417 @@
+import { mkdtempSync, rmSync } from 'node:fs';
+import { tmpdir } from 'node:os';
+import { join } from 'node:path';
+import { afterAll, afterEach, beforeAll, describe, expect, it, vi } from 'vitest';
+import { type GenerateOutputOptions, generate } from '../src/core/generate/index.ts';
+import { writeCoreStubs } from './utils/core-stubs.ts';
+import { testWasmInit } from './utils/wasm.ts';
+
+const TESTS_ASSETS_DIR = './tests/assets';
+const CANISTER_ID = 'ryjl3-tyaaa-aaaaa-aaaba-cai';
+
+interface HandledCall {
+  methodName: string;
+  mode: string;
+  args: unknown[];
+  canisterId: string;
+  effectiveCanisterId?: string;
+}
+
+let tmpDir: string;
+
+beforeAll(async () => {
+  await testWasmInit();
+
+  tmpDir = mkdtempSync(join(tmpdir(), 'icp-bindgen-runtime-'));
+  writeCoreStubs(tmpDir);
+});
+
+afterAll(() => {
+  if (tmpDir) rmSync(tmpDir, { recursive: true, force: true });
+});
+
+afterEach(() => {
+  vi.restoreAllMocks();
+});
+
+/**
+ * Generates the bindings of the service, and imports its actor wrapper.
+ */
+// biome-ignore lint/suspicious/noExplicitAny: the generated module is imported at runtime
+async function importActor(
+  serviceName: string,
+  actor: GenerateOutputOptions['actor'],
+): Promise<any> {
+  // The bindings are generated next to the stubs of @icp-sdk/core, in their own directory
+  // as the imported modules are cached
+  const outDir = mkdtempSync(join(tmpDir, `${serviceName}-`));
+  await generate({
+    didFile: `${TESTS_ASSETS_DIR}/${serviceName}.did`,
+    outDir,
+    output: { actor },
+  });
+
+  return await import(/* @vite-ignore */ join(outDir, `${serviceName}.ts`));
+}
+
+/**
+ * Returns an agent handling the calls of the stub actors with `handle`.
+ */
+function fakeAgent(handle: (call: HandledCall) => unknown = () => undefined) {
+  return {
+    handle: vi.fn(async (call: HandledCall) => handle(call)),
+    call: vi.fn(async () => ({
+      requestId: 'request-id',
+      response: { ok: true, status: 202, statusText: 'Accepted' },
+    })),
+  };
+}
+
+describe('interceptors', () => {
+  it('should run the interceptors around the calls', async () => {
+    const { createActor } = await importActor('oneway_receipts', { interceptors: true });
+    const events: string[] = [];
+    const interceptor = (id: string) => ({
+      before: ({ name, mode }: { name: string; mode: string }, args: unknown[]) => {
+        events.push(`${id}.before ${name} ${mode} ${JSON.stringify(args)}`);
+      },
+      after: ({ name }: { name: string }) => {
+        events.push(`${id}.after ${name}`);
+      },
+      onError: ({ name }: { name: string }, error: unknown) => {
+        events.push(`${id}.onError ${name} ${(error as Error).message}`);
+      },
+    });
+    const agent = fakeAgent(({ methodName }) => {
+      if (methodName === 'flush') {
+        throw new Error('Flush failed');
+      }
+    });
+    const actor = createActor(CANISTER_ID, {
+      agent,
+      interceptors: [interceptor('a'), interceptor('b')],
+    });
+
+    await actor.log('started');
+    await expect(actor.flush()).rejects.toThrow('Flush failed');
+
+    expect(events).toEqual([
+      'a.before log oneway ["started"]',
+      'b.before log oneway ["started"]',
+      'b.after log',
+      'a.after log',
+      'a.before flush update []',
+      'b.before flush update []',
+      'b.onError flush Flush failed',
+      'a.onError flush Flush failed',
+    ]);
+  });
+});
+
+describe('retry policy', () => {
+  const policy = { attempts: 3, backoff: 10, jitter: 0 };
+
+  it('should retry the failed queries with an exponential backoff', async () => {
+    const { createActor } = await importActor('retry_policy', { retryPolicy: true });
+    const agent = fakeAgent(() => {
+      if (agent.handle.mock.calls.length < 3) {
+        throw new TypeError('fetch failed');
+      }
+      return 5n;
+    });
+    const actor = createActor(CANISTER_ID, { agent, retryPolicy: policy });
+    const setTimeoutSpy = vi.spyOn(globalThis, 'setTimeout');
+
+    await expect(actor.get_balance('alice')).resolves.toBe(5n);
+
+    expect(agent.handle).toHaveBeenCalledTimes(3);
+    expect(setTimeoutSpy.mock.calls.map(([, delay]) => delay)).toEqual([10, 20]);
+  });
+
+  it('should stop after the maximum number of attempts', async () => {
+    const { createActor } = await importActor('retry_policy', { retryPolicy: true });
+    const agent = fakeAgent(() => {
+      throw new TypeError('fetch failed');
+    });
+    const actor = createActor(CANISTER_ID, { agent, retryPolicy: policy });
+
+    await expect(actor.get_balance('alice')).rejects.toThrow('fetch failed');
+
+    expect(agent.handle).toHaveBeenCalledTimes(3);
+  });
+
+  it('should retry the idempotent updates only', async () => {
+    const { createActor } = await importActor('retry_policy', { retryPolicy: true });
+    const agent = fakeAgent(() => {
+      throw new TypeError('fetch failed');
+    });
+    const actor = createActor(CANISTER_ID, { agent, retryPolicy: policy });
+
+    await expect(actor.transfer('alice', 'bob', 1n)).rejects.toThrow('fetch failed');
+    expect(agent.handle).toHaveBeenCalledTimes(1);
+
+    await expect(actor.set_balance('alice', 1n)).rejects.toThrow('fetch failed');
+    expect(agent.handle).toHaveBeenCalledTimes(4);
+  });
+
+  it.each([
+    ['a network failure', new TypeError('fetch failed'), 3],
+    [
+      'an unavailable replica',
+      Object.assign(new Error('Unavailable'), { response: { status: 503 } }),
+      3,
+    ],
+    ['a rate limit', Object.assign(new Error('Too many requests'), { status: 429 }), 3],
+    [
+      'a nested network failure',
+      new Error('Call failed', { cause: new TypeError('Load failed') }),
+      3,
+    ],
+    ['a bad request', Object.assign(new Error('Bad request'), { response: { status: 400 } }), 1],
+    ['a programming error', new TypeError('x is not a function'), 1],
+    ['a canister reject', new Error('Rejected'), 1],
+  ])('should retry %s as needed', async (_, error, calls) => {
+    const { createActor } = await importActor('retry_policy', { retryPolicy: true });
+    const agent = fakeAgent(() => {
+      throw error;
+    });
+    const actor = createActor(CANISTER_ID, { agent, retryPolicy: policy });
+
+    await expect(actor.get_balance('alice')).rejects.toBe(error);
+
+    expect(agent.handle).toHaveBeenCalledTimes(calls);
+  });
+});
+
+describe('query cache', () => {
+  const queryCache = { ttl: 1000, maxSize: 2 };
+
+  it('should cache the responses of the queries', async () => {
+    const { createActor } = await importActor('method_modes', { queryCache: true });
+    const agent = fakeAgent(({ args }) => (args[0] as string[]).map((name) => `Hello, ${name}!`));
+    const actor = createActor(CANISTER_ID, { agent, queryCache });
+
+    const first = await actor.greet_all(['alice']);
+    first.push('mutated');
+    const second = await actor.greet_all(['alice']);
+
+    expect(second).toEqual(['Hello, alice!']);
+    expect(second).not.toBe(first);
+    expect(agent.handle).toHaveBeenCalledTimes(1);
+  });
+
+  it('should not cache the updates', async () => {
+    const { createActor } = await importActor('method_modes', { queryCache: true });
+    const agent = fakeAgent();
+    const actor = createActor(CANISTER_ID, { agent, queryCache });
+
+    await actor.set_greeting('Hi');
+    await actor.set_greeting('Hi');
+
+    expect(agent.handle).toHaveBeenCalledTimes(2);
+  });
+
+  it('should not cache the failed calls', async () => {
+    const { createActor } = await importActor('method_modes', { queryCache: true });
+    const agent = fakeAgent(() => {
+      if (agent.handle.mock.calls.length === 1) {
+        throw new Error('Query failed');
+      }
+      return 'Hello, alice!';
+    });
+    const actor = createActor(CANISTER_ID, { agent, queryCache });
+
+    await expect(actor.greet('alice')).rejects.toThrow('Query failed');
+    await expect(actor.greet('alice')).resolves.toBe('Hello, alice!');
+    await expect(actor.greet('alice')).resolves.toBe('Hello, alice!');
+
+    expect(agent.handle).toHaveBeenCalledTimes(2);
+  });
+
+  it('should expire the responses after the ttl', async () => {
+    const { createActor } = await importActor('method_modes', { queryCache: true });
+    const agent = fakeAgent(() => 'Hello, alice!');
+    const actor = createActor(CANISTER_ID, { agent, queryCache });
+    const now = vi.spyOn(Date, 'now').mockReturnValue(0);
+
+    await actor.greet('alice');
+    now.mockReturnValue(999);
+    await actor.greet('alice');
+    expect(agent.handle).toHaveBeenCalledTimes(1);
+
+    now.mockReturnValue(1000);
+    await actor.greet('alice');
+    expect(agent.handle).toHaveBeenCalledTimes(2);
+  });
+
+  it('should evict the oldest responses', async () => {
+    const { createActor } = await importActor('method_modes', { queryCache: true });
+    const agent = fakeAgent(({ args }) => `Hello, ${args[0]}!`);
+    const actor = createActor(CANISTER_ID, { agent, queryCache });
+
+    await actor.greet('alice');
+    await actor.greet('bob');
+    await actor.greet('carol');
+    expect(agent.handle).toHaveBeenCalledTimes(3);
+
+    await actor.greet('carol');
+    expect(agent.handle).toHaveBeenCalledTimes(3);
+
+    await actor.greet('alice');
+    expect(agent.handle).toHaveBeenCalledTimes(4);
+  });
+
+  it('should invalidate the responses', async () => {
+    const { createActor } = await importActor('method_modes', { queryCache: true });
+    const agent = fakeAgent(({ args }) =>
+      Array.isArray(args[0]) ? args[0].map((name) => `Hello, ${name}!`) : `Hello, ${args[0]}!`,
+    );
+    const actor = createActor(CANISTER_ID, { agent, queryCache });
+
+    await actor.greet('alice');
+    await actor.greet_all(['alice']);
+    actor.invalidate('greet');
+    await actor.greet('alice');
+    await actor.greet_all(['alice']);
+    expect(agent.handle).toHaveBeenCalledTimes(3);
+
+    actor.invalidate();
+    await actor.greet('alice');
+    await actor.greet_all(['alice']);
+    expect(agent.handle).toHaveBeenCalledTimes(5);
+  });
+});
+
+describe('call options', () => {
+  it('should make the calls with the options', async () => {
+    const { createActor } = await importActor('call_options', { callOptions: true });
+    const agent = fakeAgent(() => 1n);
+    const otherAgent = fakeAgent(() => 2n);
+    const actor = createActor(CANISTER_ID, { agent });
+
+    await expect(actor.withOptions({ agent: otherAgent }).balance()).resolves.toBe(2n);
+    await expect(actor.balance()).resolves.toBe(1n);
+
+    expect(otherAgent.handle).toHaveBeenCalledTimes(1);
+    expect(agent.handle).toHaveBeenCalledTimes(1);
+  });
+
+  it('should stop waiting for the calls once aborted', async () => {
+    const { createActor } = await importActor('call_options', { callOptions: true });
+    const agent = fakeAgent(() => new Promise(() => {}));
+    const actor = createActor(CANISTER_ID, { agent });
+    const controller = new AbortController();
+
+    const call = actor.withOptions({ signal: controller.signal }).transfer(1n);
+    controller.abort(new Error('Aborted'));
+
+    await expect(call).rejects.toThrow('Aborted');
+  });
+
+  it('should reject the calls once aborted', async () => {
+    const { createActor } = await importActor('call_options', { callOptions: true });
+    const agent = fakeAgent(() => 1n);
+    const actor = createActor(CANISTER_ID, { agent });
+
+    await expect(
+      actor.withOptions({ signal: AbortSignal.abort(new Error('Aborted')) }).balance(),
+    ).rejects.toThrow('Aborted');
+  });
+});
+
+describe('certified queries', () => {
+  it('should call the queries of the certified actor as updates', async () => {
+    const { createActor } = await importActor('certified_queries', { certifiedQueries: true });
+    const agent = fakeAgent(() => 1n);
+    const actor = createActor(CANISTER_ID, { agent });
+
+    await actor.balance();
+    await actor.certified.balance();
+    await actor.certified.transfer(1n);
+
+    const calls = agent.handle.mock.calls.map(([{ methodName, mode }]) => `${methodName} ${mode}`);
+    expect(calls).toEqual(['balance query', 'balance update', 'transfer update']);
+  });
+});
+
+describe('oneway receipts', () => {
+  it('should submit the oneway calls without waiting', async () => {
+    const { createActor } = await importActor('oneway_receipts', { onewayReceipts: true });
+    const agent = fakeAgent();
+    const actor = createActor(CANISTER_ID, { agent });
+
+    await expect(actor.log('started')).resolves.toEqual({
+      methodName: 'log',
+      requestId: 'request-id',
+    });
+
+    expect(agent.handle).not.toHaveBeenCalled();
+    expect(agent.call).toHaveBeenCalledTimes(1);
+    const [canisterId, options] = agent.call.mock.lastCall as unknown as [
+      { toText(): string },
+      { methodName: string; effectiveCanisterId: { toText(): string }; callSync: boolean },
+    ];
+    expect(canisterId.toText()).toBe(CANISTER_ID);
+    expect(options).toMatchObject({ methodName: 'log', callSync: false });
+    expect(options.effectiveCanisterId.toText()).toBe(CANISTER_ID);
+  });
+
+  it('should throw if the oneway calls could not be submitted', async () => {
+    const { createActor, OnewaySubmissionError } = await importActor('oneway_receipts', {
+      onewayReceipts: true,
+    });
+    const agent = fakeAgent();
+    agent.call.mockResolvedValue({
+      requestId: 'request-id',
+      response: { ok: false, status: 503, statusText: 'Service Unavailable' },
+    });
+    const actor = createActor(CANISTER_ID, { agent });
+
+    const error = await actor.log('started').catch((error: unknown) => error);
+
+    expect(error).toBeInstanceOf(OnewaySubmissionError);
+    expect(error).toMatchObject({ methodName: 'log' });
+  });
+});
+
+describe('canister errors', () => {
+  it.each([
+    [
+      'CanisterTrapError',
+      { rejectCode: 5, rejectMessage: 'Canister trapped', rejectErrorCode: 'IC0503' },
+    ],
+    [
+      'CanisterRejectError',
+      { rejectCode: 4, rejectMessage: 'Unauthorized', rejectErrorCode: 'IC0406' },
+    ],
+  ])('should throw a %s for the rejects', async (errorClass, reject) => {
+    const bindings = await importActor('canister_errors', { canisterErrors: true });
+    const cause = new Error('Call failed', { cause: reject });
+    const agent = fakeAgent(() => {
+      throw cause;
+    });
+    const actor = bindings.createActor(CANISTER_ID, { agent });
+
+    const error = await actor.greet('alice').catch((error: unknown) => error);
+
+    expect(error).toBeInstanceOf(bindings[errorClass]);
+    expect(error).toMatchObject({
+      methodName: 'greet',
+      rejectCode: reject.rejectCode,
+      rejectMessage: reject.rejectMessage,
+      errorCode: reject.rejectErrorCode,
+      error: cause,
+    });
+  });
+
+  it('should rethrow the other errors', async () => {
+    const { createActor } = await importActor('canister_errors', { canisterErrors: true });
+    const cause = new TypeError('fetch failed');
+    const agent = fakeAgent(() => {
+      throw cause;
+    });
+    const actor = createActor(CANISTER_ID, { agent });
+
+    await expect(actor.greet('alice')).rejects.toBe(cause);
+  });
+});
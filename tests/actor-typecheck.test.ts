@@ -0,0 +1,92 @@
+import { mkdtempSync, readFileSync, rmSync, writeFileSync } from 'node:fs';
+import { tmpdir } from 'node:os';
+import { join } from 'node:path';
+import { globSync } from 'tinyglobby';
+import ts from 'typescript';
+import { afterAll, beforeAll, describe, expect, it } from 'vitest';
+import { type GenerateOutputOptions, generate } from '../src/core/generate/index.ts';
+import { writeCoreStubs } from './utils/core-stubs.ts';
+import { testWasmInit } from './utils/wasm.ts';
+
+const TESTS_ASSETS_DIR = './tests/assets';
+
+const COMPILER_OPTIONS: ts.CompilerOptions = {
+  target: ts.ScriptTarget.ES2023,
+  module: ts.ModuleKind.ESNext,
+  moduleResolution: ts.ModuleResolutionKind.Bundler,
+  strict: true,
+  noEmit: true,
+};
+
+let tmpDir: string;
+
+beforeAll(async () => {
+  await testWasmInit();
+
+  tmpDir = mkdtempSync(join(tmpdir(), 'icp-bindgen-actor-typecheck-'));
+  writeCoreStubs(tmpDir);
+});
+
+afterAll(() => {
+  if (tmpDir) rmSync(tmpDir, { recursive: true, force: true });
+});
+
+describe('typecheck the generated actors', () => {
+  it.each<[string, GenerateOutputOptions['actor']]>([
+    ['hello_world', {}],
+    ['example', { optionalFields: true }],
+    ['camel_case', { camelCase: true }],
+    ['input_types', { inputTypes: true }],
+    ['branded_types', { brandedTypes: true, readonlyTypes: true }],
+    ['principal_text', { principalFormat: 'text' }],
+    ['service_refs', { serviceRefs: true }],
+    ['func_refs', { funcRefs: true }],
+    ['service_classes', { serviceClasses: true }],
+    ['method_modes', { methodModes: true, queryCache: true }],
+    ['oneway_receipts', { onewayReceipts: true, interceptors: true }],
+    ['certified_queries', { certifiedQueries: true }],
+    ['call_options', { callOptions: true, certifiedQueries: true }],
+    ['or_throw_methods', { orThrowMethods: true, canisterErrors: true }],
+    ['canister_errors', { canisterErrors: true }],
+    ['retry_policy', { retryPolicy: true }],
+    [
+      'method_modes',
+      {
+        methodModes: true,
+        onewayReceipts: true,
+        certifiedQueries: true,
+        callOptions: true,
+        orThrowMethods: true,
+        canisterErrors: true,
+        interceptors: true,
+        retryPolicy: true,
+        queryCache: true,
+      },
+    ],
+  ])('%s with %j', async (serviceName, actor) => {
+    const outDir = mkdtempSync(join(tmpDir, `${serviceName}-`));
+    await generate({
+      didFile: `${TESTS_ASSETS_DIR}/${serviceName}.did`,
+      outDir,
+      output: { actor: { ...actor, interfaceFile: true } },
+    });
+
+    // The generated files opt out of type checking, for the projects with other compiler options
+    const files = globSync('**/*.ts', { cwd: outDir, absolute: true });
+    for (const file of files) {
+      writeFileSync(file, readFileSync(file, 'utf-8').replace('// @ts-nocheck\n', ''));
+    }
+
+    const program = ts.createProgram(files, COMPILER_OPTIONS);
+    const diagnostics = ts.getPreEmitDiagnostics(program);
+
+    if (diagnostics.length > 0) {
+      const formatted = ts.formatDiagnosticsWithColorAndContext(diagnostics, {
+        getCanonicalFileName: (f) => f,
+        getCurrentDirectory: () => outDir,
+        getNewLine: () => '\n',
+      });
+      expect.fail(`TypeScript errors in the actor of ${serviceName}:\n${formatted}`);
+    }
+  });
+});
@@ -258,6 +258,882 @@ describe('generate', () => {
     expect(serviceTs).not.toContain(`from "./declarations/${serviceName}.did"`);
   });
 
+  it('should send the falsy optional fields and omit the absent ones', async () => {
+    const serviceName = 'optional_fields';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { optionalFields: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    // 0, "" and false are present values, only null or undefined are absent
+    expect(serviceTs).toContain('count: value.count == null ? candid_none() : candid_some(value.count)');
+    expect(serviceTs).toContain('label: value.label == null ? candid_none() : candid_some(value.label)');
+    expect(serviceTs).toContain('enabled: value.enabled == null ? candid_none() : candid_some(value.enabled)');
+    // For `opt opt nat`, null is the inner absent value
+    expect(serviceTs).toContain('limit: value.limit === undefined ? candid_none() : candid_some(');
+    expect(serviceTs).toContain('...(value.count.length === 0 ? {} : {');
+    expect(serviceTs).toContain('...(value.limit.length === 0 ? {} : {');
+    expect(serviceTs).not.toContain('record_opt_to_undefined');
+  });
+
+  it('should keep the default conversions of the optional fields', async () => {
+    const serviceName = 'optional_fields';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('count: value.count ? candid_some(value.count) : candid_none()');
+    expect(serviceTs).toContain('count: record_opt_to_undefined(');
+    expect(serviceTs).not.toContain('.length === 0 ? {} : {');
+  });
+
+  it('should generate maps for all association lists', async () => {
+    const serviceName = 'maps';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { mapTypes: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('export type Metadata = Map<string, Value>;');
+    // The entries are converted to Candid tuples and back
+    expect(serviceTs).toMatch(/return Array\.from\(value, \(x\)=>to_candid_\w+_n\d+\(x\)\);/);
+    expect(serviceTs).toMatch(/return new Map\(value\.map\(\(x\)=>from_candid_\w+_n\d+\(x\)\)\);/);
+    // A Map would compare the Principal keys by identity
+    expect(serviceTs).toContain('set_balances(arg0: Array<[Principal, bigint]>): Promise<void>;');
+  });
+
+  it('should generate maps for the association lists with principal text keys', async () => {
+    const serviceName = 'maps';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { mapTypes: true, principalFormat: 'text' } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('set_balances(arg0: Map<string, bigint>): Promise<void>;');
+  });
+
+  it('should generate maps for the named association lists only', async () => {
+    const serviceName = 'maps';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { mapTypes: ['Metadata'] } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('export type Metadata = Map<string, Value>;');
+    expect(serviceTs).toContain('set_balances(arg0: Array<[Principal, bigint]>): Promise<void>;');
+  });
+
+  it('should generate camelCase names', async () => {
+    const serviceName = 'camel_case';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { camelCase: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('displayName: string;');
+    expect(serviceTs).toContain('isActive?: boolean;');
+    expect(serviceTs).toContain('getUserProfile(userId: string): Promise<user_profile | null>;');
+    expect(serviceTs).toContain('async updateUserProfile(arg0: user_profile): Promise<void> {');
+    expect(serviceTs).toContain('this.actor.get_user_profile(');
+    expect(serviceTs).toContain('display_name: value.displayName');
+    expect(serviceTs).toContain('displayName: value.display_name');
+  });
+
+  it('should reject camelCase names that collide', async () => {
+    const didFile = `${TESTS_ASSETS_DIR}/camel_case_collision.did`;
+
+    await expect(
+      generate({
+        didFile,
+        outDir: OUTPUT_DIR,
+        output: { actor: { camelCase: true } },
+      }),
+    ).rejects.toThrow('The Candid names `user_id` and `userId` both map to `userId` in camelCase.');
+  });
+
+  it('should reject the camelCase names colliding with hashed labels', async () => {
+    const didFile = `${TESTS_ASSETS_DIR}/camel_case_labels.did`;
+
+    await expect(
+      generate({
+        didFile,
+        outDir: OUTPUT_DIR,
+        output: { actor: { camelCase: true } },
+      }),
+    ).rejects.toThrow('both map to `_42_` in camelCase.');
+  });
+
+  it('should reject the camelCase method names colliding with the actor class members', async () => {
+    const didFile = `${TESTS_ASSETS_DIR}/camel_case_members.did`;
+
+    await expect(
+      generate({
+        didFile,
+        outDir: OUTPUT_DIR,
+        output: { actor: { camelCase: true, callOptions: true } },
+      }),
+    ).rejects.toThrow(
+      'The Candid method `with_options` maps to `withOptions`, which is a member of the generated actor class. Rename the method or disable the `callOptions` option.',
+    );
+  });
+
+  it.each([
+    [{ queryCache: true }, 'The Candid method `invalidate` maps to `invalidate`'],
+    [
+      { orThrowMethods: true },
+      'The Candid method `transferOrThrow` maps to `transferOrThrow`, which is the `...OrThrow` variant of `transfer`.',
+    ],
+  ])('should reject the method names colliding with the generated members with %o', async (actor, message) => {
+    const didFile = `${TESTS_ASSETS_DIR}/class_members.did`;
+
+    await expect(generate({ didFile, outDir: OUTPUT_DIR, output: { actor } })).rejects.toThrow(
+      message,
+    );
+  });
+
+  it('should generate user-defined type overrides', async () => {
+    const serviceName = 'type_overrides';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: {
+        actor: {
+          interfaceFile: true,
+          typeOverrides: {
+            Timestamp: {
+              type: 'DateTime',
+              module: '../utils/timestamp',
+              toCandid: 'dateTimeToNanos',
+              fromCandid: 'nanosToDateTime',
+            },
+          },
+        },
+      },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain(
+      'import { type DateTime, dateTimeToNanos, nanosToDateTime } from "../utils/timestamp";',
+    );
+    expect(serviceTs).toContain('export type Timestamp = DateTime;');
+    expect(serviceTs).toContain('at: Timestamp;');
+    expect(serviceTs).toContain('return dateTimeToNanos(value);');
+    expect(serviceTs).toContain('return nanosToDateTime(value);');
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain('import { type DateTime } from "../utils/timestamp";');
+    expect(interfaceTs).not.toContain('dateTimeToNanos');
+  });
+
+  it('should reject the type overrides of unknown types', async () => {
+    const didFile = `${TESTS_ASSETS_DIR}/type_overrides.did`;
+
+    await expect(
+      generate({
+        didFile,
+        outDir: OUTPUT_DIR,
+        output: {
+          actor: {
+            typeOverrides: {
+              Timestmap: {
+                type: 'DateTime',
+                module: '../utils/timestamp',
+                toCandid: 'dateTimeToNanos',
+                fromCandid: 'nanosToDateTime',
+              },
+            },
+          },
+        },
+      }),
+    ).rejects.toThrow('Unknown type `Timestmap` in the type overrides.');
+  });
+
+  it('should reject the type overrides with invalid names', async () => {
+    const didFile = `${TESTS_ASSETS_DIR}/type_overrides.did`;
+
+    await expect(
+      generate({
+        didFile,
+        outDir: OUTPUT_DIR,
+        output: {
+          actor: {
+            typeOverrides: {
+              Timestamp: {
+                type: 'DateTime',
+                module: '../utils/timestamp',
+                toCandid: 'dateTimeToNanos; alert(1)',
+                fromCandid: 'nanosToDateTime',
+              },
+            },
+          },
+        },
+      }),
+    ).rejects.toThrow(
+      'Invalid name `dateTimeToNanos; alert(1)` in the type override of `Timestamp`, expected an identifier.',
+    );
+  });
+
+  it('should generate the well-known ICRC types', async () => {
+    const serviceName = 'icrc_ledger';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { interfaceFile: true, icrcTypes: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('import type * as icrc from "./icrc";');
+    expect(serviceTs).toContain('export type Account = icrc.Account;');
+    expect(serviceTs).toContain('export type Tokens = icrc.Tokens;');
+    expect(serviceTs).toContain('export type TransferError = icrc.TransferError;');
+    expect(serviceTs).not.toContain('export type BlockIndex = icrc.');
+    expect(serviceTs).toContain('export interface icrc_ledgerInterface extends icrc.Icrc1Interface {');
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain('import type * as icrc from "./icrc";');
+
+    const icrcTs = await readFileFromOutput('icrc.ts');
+    expect(icrcTs).toContain('import { Principal } from "@icp-sdk/core/principal";');
+    expect(icrcTs).toContain('export interface Account {');
+    expect(icrcTs).toContain('export interface Icrc1Interface {');
+    expect(icrcTs).toContain('export interface Icrc2Interface {');
+    expect(icrcTs).toContain('export function encodeAccount(account: Account): string {');
+    expect(icrcTs).toContain('export function decodeAccount(text: string): Account {');
+    expect(icrcTs).toContain('function account_owner(owner: Principal): Principal {');
+  });
+
+  it('should generate a separate ICRC module for the options shaping the ICRC types', async () => {
+    const serviceName = 'icrc_ledger';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { icrcTypes: true, camelCase: true, principalFormat: 'text' } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('import type * as icrc from "./icrc-camel-text";');
+    expect(fileExists(`${OUTPUT_DIR}/icrc.ts`)).toBe(false);
+
+    // The owner of the decoded accounts is printed as text
+    const icrcTs = await readFileFromOutput('icrc-camel-text.ts');
+    expect(icrcTs).toContain('function account_owner(owner: Principal): string {');
+    expect(icrcTs).toContain('return owner.toText();');
+  });
+
+  it('should overwrite a stale ICRC module', async () => {
+    const serviceName = 'icrc_ledger';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+    vol.mkdirSync(OUTPUT_DIR, { recursive: true });
+    vol.writeFileSync(`${OUTPUT_DIR}/icrc.ts`, '// stale', { encoding: 'utf-8' });
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { icrcTypes: true } },
+    });
+
+    const icrcTs = await readFileFromOutput('icrc.ts');
+    expect(icrcTs).not.toContain('// stale');
+    expect(icrcTs).toContain('export interface Account {');
+  });
+
+  it.each([
+    { inputTypes: true },
+    { brandedTypes: true },
+  ])('should keep the ICRC types inline with %o', async (options) => {
+    const serviceName = 'icrc_ledger';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { icrcTypes: true, ...options } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).not.toContain('import type * as icrc');
+    expect(serviceTs).toContain('export interface Account {');
+    expect(fileExists(`${OUTPUT_DIR}/icrc.ts`)).toBe(false);
+  });
+
+  it('should reject a service named after the ICRC module', async () => {
+    const didFile = `${TESTS_ASSETS_DIR}/icrc.did`;
+
+    await expect(
+      generate({
+        didFile,
+        outDir: OUTPUT_DIR,
+        output: { actor: { icrcTypes: true } },
+      }),
+    ).rejects.toThrow('The service name `icrc` collides with the shared ICRC module.');
+  });
+
+  it('should generate lenient input types', async () => {
+    const serviceName = 'input_types';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { interfaceFile: true, inputTypes: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('import { Principal } from "@icp-sdk/core/principal";');
+    expect(serviceTs).toContain('export type Input<T> = T extends bigint ? bigint | number');
+    expect(serviceTs).toContain('export type ProfileInput = Input<Profile>;');
+    expect(serviceTs).toContain('async set_profile(arg0: ProfileInput): Promise<void> {');
+    expect(serviceTs).toContain('async get_profile(arg0: Input<Principal>): Promise<Profile | null> {');
+    expect(serviceTs).toContain('async set_limit(arg0: Input<bigint>, arg1: number): Promise<void> {');
+    expect(serviceTs).toContain('typeof value === "string" ? Principal.fromText(value) : value');
+    expect(serviceTs).toContain('return bigint_input(value);');
+    expect(serviceTs).toContain(
+      'if (typeof value === "number" && !Number.isSafeInteger(value)) {',
+    );
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain('import type { Principal } from "@icp-sdk/core/principal";');
+    expect(interfaceTs).toContain('set_profile(arg0: ProfileInput): Promise<void>;');
+  });
+
+  it('should generate named objects for multiple return values', async () => {
+    const serviceName = 'named_returns';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { namedReturns: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('count: bigint;');
+    expect(serviceTs).toContain('items: Array<Item>;');
+    expect(serviceTs).toContain('_0: string;');
+    expect(serviceTs).toContain('_1: bigint | null;');
+    expect(serviceTs).toContain('count: result[0],');
+    expect(serviceTs).toContain('items: result[1]');
+    expect(serviceTs).toContain('_0: result[0],');
+  });
+
+  it('should use the Candid argument names', async () => {
+    const serviceName = 'named_args';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('async greet(name: string): Promise<string>');
+    expect(serviceTs).toContain('this.actor.greet(name)');
+    expect(serviceTs).toContain(
+      'async transfer(to: Principal, amount: bigint, arg2: Uint8Array | null): Promise<void>',
+    );
+    expect(serviceTs).toContain('async remove(class_: string, result_: bigint): Promise<void>');
+    expect(serviceTs).toContain('this.actor.remove(class_, result_)');
+  });
+
+  it('should generate named-argument objects', async () => {
+    const serviceName = 'named_args';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { namedArgs: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('greet(name: string): Promise<string>;');
+    expect(serviceTs).toContain('async remove({ class_, result_ }: {');
+    expect(serviceTs).toContain('transfer(args: {');
+    expect(serviceTs).toContain('arg2: Uint8Array | null;');
+    expect(serviceTs).toContain('this.actor.transfer(to, amount, to_candid_opt_');
+  });
+
+  it('should generate branded types', async () => {
+    const serviceName = 'branded_types';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { brandedTypes: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('export type CanisterId = Principal & {');
+    expect(serviceTs).toContain('readonly __brand: "CanisterId";');
+    expect(serviceTs).toContain('export type Tokens = bigint & {');
+    expect(serviceTs).toContain('export type Owner = UserId;');
+    expect(serviceTs).toContain('export function UserId(value: Principal): UserId {');
+    expect(serviceTs).toContain('return value as UserId;');
+    expect(serviceTs).toContain('async get_controller(arg0: CanisterId): Promise<UserId>');
+  });
+
+  it('should generate readonly types', async () => {
+    const serviceName = 'readonly_types';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { readonlyTypes: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('readonly id: bigint;');
+    expect(serviceTs).toContain('readonly tags: ReadonlyArray<string>;');
+    expect(serviceTs).toContain('get_items(): Promise<ReadonlyArray<Item>>;');
+    expect(serviceTs).toContain('get_pair(): Promise<readonly [bigint, string]>;');
+    expect(serviceTs).toContain('set_tags(arg0: ReadonlyArray<string>): Promise<void>;');
+    expect(serviceTs).toContain('return Array.from(value);');
+    expect(serviceTs).not.toContain('Object.freeze');
+  });
+
+  it('should freeze the results', async () => {
+    const serviceName = 'readonly_types';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { readonlyTypes: true, freezeResults: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('return Object.freeze(');
+    expect(serviceTs).toMatch(/const result = await this\.actor\.get_pair\(\);\s+return from_candid_/);
+  });
+
+  it('should name hashed labels with the label dictionary', async () => {
+    const serviceName = 'label_names';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { labelNames: ['name', 'balance'] } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('name: string;');
+    expect(serviceTs).toContain('balance?: bigint;');
+    expect(serviceTs).toContain('_42_: bigint;');
+    expect(serviceTs).toContain('name: value._1224700491_');
+    expect(serviceTs).toContain('_1224700491_: value.name');
+    expect(serviceTs).toContain('balance: value._596483356_[0]');
+  });
+
+  it('should represent principals as text', async () => {
+    const serviceName = 'principal_text';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { principalFormat: 'text' } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('import { Principal } from "@icp-sdk/core/principal";');
+    expect(serviceTs).toContain('id: string;');
+    expect(serviceTs).toContain('get_user(arg0: string): Promise<User | null>;');
+    expect(serviceTs).toContain('whoami(): Promise<string>;');
+    expect(serviceTs).toContain('Principal.fromText(');
+    expect(serviceTs).toContain('.toText()');
+  });
+
+  it('should print the branded principals as text', async () => {
+    const serviceName = 'branded_types';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { brandedTypes: true, principalFormat: 'text' } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('export type UserId = string & {');
+    expect(serviceTs).toContain('export function UserId(value: string): UserId {');
+    // The branded value is converted like its underlying type, then branded
+    expect(serviceTs).toContain('return value.toText() as UserId;');
+    expect(serviceTs).toContain('return Principal.fromText(value);');
+  });
+
+  it('should type service references with their interface', async () => {
+    const serviceName = 'service_refs';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { serviceRefs: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('declare const __service: unique symbol;');
+    expect(serviceTs).toContain('readonly [__service]: T;');
+    expect(serviceTs).toContain(
+      'export function ServiceRef<T>(principal: Principal): ServiceRef<T> {',
+    );
+    expect(serviceTs).toContain(' as ServiceRef<LedgerInterface>');
+    expect(serviceTs).toContain('get_ledger(): Promise<ServiceRef<LedgerInterface>>;');
+    expect(serviceTs).toContain('spawn(): Promise<ServiceRef<{');
+    expect(serviceTs).toContain(
+      'export function fromServiceRef<T>(ref: ServiceRef<T>, create: (canisterId: string, options?: CreateActorOptions) => T, options?: CreateActorOptions): T {',
+    );
+    expect(serviceTs).toContain('return create(ref.toString(), options);');
+  });
+
+  it('should generate callable constructors for function types', async () => {
+    const serviceName = 'func_refs';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { interfaceFile: true, funcRefs: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('idlReferenceTypes } from "./declarations/func_refs.did";');
+    expect(serviceTs).toContain(
+      'export function GetBlocksFn(func: [Principal, string], agent: Agent): GetBlocksFn {',
+    );
+    expect(serviceTs).toContain('Actor.createActor<Record<string, _GetBlocksFn>>');
+    expect(serviceTs).toContain('idlReferenceTypes({');
+    expect(serviceTs).toContain('const result = await actor[func[1]](start, length);');
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain(
+      'export declare function GetBlocksFn(func: [Principal, string], agent: Agent): GetBlocksFn;',
+    );
+
+    const declarationsJs = await readFileFromOutput(`declarations/${serviceName}.did.js`);
+    expect(declarationsJs).toContain('export const idlReferenceTypes = ({ IDL }) => {');
+    const declarationsTs = await readFileFromOutput(`declarations/${serviceName}.did.d.ts`);
+    expect(declarationsTs).toContain(
+      'export declare const idlReferenceTypes: (args: { IDL: typeof IDL }) =>',
+    );
+  });
+
+  it('should generate actor classes for named service types', async () => {
+    const serviceName = 'service_classes';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { interfaceFile: true, serviceClasses: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('idlFactories } from "./declarations/service_classes.did";');
+    expect(serviceTs).toContain('export class Subscriber implements SubscriberInterface {');
+    expect(serviceTs).toContain('constructor(private actor: ActorSubclass<_Subscriber>){}');
+    expect(serviceTs).toContain('export function createSubscriberActor(canisterId: string');
+    expect(serviceTs).toContain('idlFactories.Subscriber');
+    expect(serviceTs).toContain('return new Subscriber(actor);');
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain(
+      'export declare function createSubscriberActor(canisterId: string, options: CreateActorOptions = {}): SubscriberInterface;',
+    );
+
+    const declarationsJs = await readFileFromOutput(`declarations/${serviceName}.did.js`);
+    expect(declarationsJs).toContain('export const idlFactories = {');
+    const declarationsTs = await readFileFromOutput(`declarations/${serviceName}.did.d.ts`);
+    expect(declarationsTs).toContain(
+      "export declare const idlFactories: { 'Subscriber' : IDL.InterfaceFactory };",
+    );
+  });
+
+  it('should retry the idempotent methods of the named service types', async () => {
+    const serviceName = 'service_classes';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { serviceClasses: true, retryPolicy: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toMatch(
+      /return new Subscriber\(with_retry_policy\(actor, options\.retryPolicy, \[\s*"notify"\s*\]\)\);/,
+    );
+  });
+
+  it('should expose the call modes of the methods', async () => {
+    const serviceName = 'method_modes';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { interfaceFile: true, methodModes: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('static readonly methodModes = {');
+    expect(serviceTs).toContain('greet: "query"');
+    expect(serviceTs).toContain('greet_all: "composite_query"');
+    expect(serviceTs).toContain('set_greeting: "update"');
+    expect(serviceTs).toContain('log: "oneway"');
+    expect(serviceTs).toContain('} as const;');
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain('* @query');
+    expect(interfaceTs).toContain('* @composite_query');
+    expect(interfaceTs).toContain('* @update');
+    expect(interfaceTs).toContain('* @oneway');
+  });
+
+  it('should submit oneway methods without waiting', async () => {
+    const serviceName = 'oneway_receipts';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { onewayReceipts: true, interfaceFile: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('async log(arg0: string): Promise<OnewayReceipt> {');
+    expect(serviceTs).toContain('return await this.actor.log(arg0);');
+    expect(serviceTs).toContain(
+      'constructor(private actor: ActorSubclass<WithOnewayReceipts<_SERVICE, "log">>){}',
+    );
+    expect(serviceTs).toMatch(
+      /return new Oneway_receipts\(with_oneway_receipts\(actor, \[\s*"log"\s*\], options\.actorOptions\)\);/,
+    );
+    expect(serviceTs).toContain('async flush(): Promise<void> {');
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain('log(arg0: string): Promise<OnewayReceipt>;');
+    expect(interfaceTs).toContain('export interface OnewayReceipt {');
+    expect(interfaceTs).toContain('export type WithOnewayReceipts<T, K extends keyof T> = ');
+  });
+
+  it('should generate certified actors', async () => {
+    const serviceName = 'certified_queries';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { certifiedQueries: true, interfaceFile: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('get certified(): Certified_queries {');
+    expect(serviceTs).toContain('return new Certified_queries(certified_actor(this.actor));');
+    expect(serviceTs).toContain(
+      'const certifiedActor = Actor.createActor<_SERVICE>(certified_idl_factory(idlFactory), {',
+    );
+    expect(serviceTs).toContain(
+      'return new Certified_queries(with_certified_actor(actor, certifiedActor));',
+    );
+    expect(serviceTs).not.toContain('_fields');
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain('readonly certified: certified_queriesInterface;');
+  });
+
+  it('should generate actors with call options', async () => {
+    const serviceName = 'call_options';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { callOptions: true, interfaceFile: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('withOptions(options: CallOptions): Call_options {');
+    expect(serviceTs).toContain('return new Call_options(with_call_options(this.actor, options));');
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain('withOptions(options: CallOptions): call_optionsInterface;');
+    expect(interfaceTs).toContain('signal?: AbortSignal;');
+  });
+
+  it('should generate methods throwing the errors of results', async () => {
+    const serviceName = 'or_throw_methods';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { orThrowMethods: true, interfaceFile: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain(
+      'async transferOrThrow(...args: Parameters<or_throw_methodsInterface["transfer"]>): Promise<bigint> {',
+    );
+    expect(serviceTs).toContain('const result = await this.transfer(...args);');
+    expect(serviceTs).toContain('throw new Or_throw_methodsError("transfer", result.Err);');
+    expect(serviceTs).toContain('return result.Ok;');
+    expect(serviceTs).toContain('async approveOrThrow(');
+    expect(serviceTs).not.toContain('balanceOrThrow');
+    expect(serviceTs).not.toContain('pingOrThrow');
+    expect(serviceTs).toContain('export class Or_throw_methodsError<E = unknown> extends Error {');
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain(
+      'transferOrThrow(...args: Parameters<or_throw_methodsInterface["transfer"]>): Promise<bigint>;',
+    );
+    expect(interfaceTs).toContain('export declare class Or_throw_methodsError<E = unknown> extends Error {');
+    expect(interfaceTs).toContain(
+      '@throws {Or_throw_methodsError<Extract<Awaited<ReturnType<or_throw_methodsInterface["transfer"]>>, { __kind__: "Err" }>["Err"]>}',
+    );
+  });
+
+  it('should translate the errors of the calls', async () => {
+    const serviceName = 'canister_errors';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { canisterErrors: true, interfaceFile: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('await with_canister_errors("greet", this.actor.greet(arg0));');
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain('export declare class CanisterRejectError extends Error {');
+    expect(interfaceTs).toContain('export declare class CanisterTrapError extends CanisterRejectError {');
+    expect(interfaceTs).toContain(
+      'constructor(methodName: string, rejectCode: number, rejectMessage: string',
+    );
+  });
+
+  it('should intercept the calls of the actors', async () => {
+    const serviceName = 'hello_world';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { interceptors: true, interfaceFile: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('interceptors?: Interceptor[];');
+    expect(serviceTs).toContain(
+      'return new Hello_world(with_interceptors(actor, options.interceptors));',
+    );
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain('interceptors?: Interceptor[];');
+    expect(interfaceTs).toContain('export interface InterceptedMethod {');
+    expect(interfaceTs).toContain(
+      'before?(method: InterceptedMethod, args: readonly unknown[]): void | Promise<void>;',
+    );
+  });
+
+  it('should intercept the oneway and the certified calls', async () => {
+    const serviceName = 'oneway_receipts';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { interceptors: true, onewayReceipts: true, certifiedQueries: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toMatch(
+      /with_interceptors\(with_oneway_receipts\(certifiedActor, \[\s*"log"\s*\], options\.actorOptions\), options\.interceptors\)/,
+    );
+  });
+
+  it('should retry the queries and the idempotent methods', async () => {
+    const serviceName = 'retry_policy';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: {
+        actor: { retryPolicy: true, idempotentMethods: ['reset_balance'], interfaceFile: true },
+      },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('retryPolicy?: RetryPolicy;');
+    expect(serviceTs).toMatch(
+      /return new Retry_policy\(with_retry_policy\(actor, options\.retryPolicy, \[\s*"reset_balance",\s*"set_balance"\s*\]\)\);/,
+    );
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain('retryPolicy?: RetryPolicy;');
+    expect(interfaceTs).toContain('export interface RetryPolicy {');
+  });
+
+  it('should cache the responses of the query methods', async () => {
+    const serviceName = 'method_modes';
+    const didFile = `${TESTS_ASSETS_DIR}/${serviceName}.did`;
+
+    await generate({
+      didFile,
+      outDir: OUTPUT_DIR,
+      output: { actor: { queryCache: true, interfaceFile: true } },
+    });
+
+    const serviceTs = await readFileFromOutput(`${serviceName}.ts`);
+    expect(serviceTs).toContain('queryCache?: QueryCacheOptions;');
+    expect(serviceTs.match(/export interface QueryCacheOptions \{/g)).toHaveLength(1);
+    expect(serviceTs).toContain(
+      'return new Method_modes(with_query_cache(actor, options.queryCache));',
+    );
+    expect(serviceTs).toMatch(
+      /invalidate\(methodName\?: string\): void \{\s+invalidate_query_cache\(this\.actor, methodName\);/,
+    );
+
+    const interfaceTs = await readFileFromOutput(`${serviceName}.d.ts`);
+    expect(interfaceTs).toContain('invalidate(methodName?: string): void;');
+  });
+
   it('should preserve the .did file', async () => {
     const { readFile: realReadFile } =
       await vi.importActual<typeof import('node:fs/promises')>('node:fs/promises');
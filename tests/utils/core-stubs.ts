@@ -0,0 +1,293 @@
+import { mkdirSync, writeFileSync } from 'node:fs';
+import { join } from 'node:path';
+
+// Minimal stubs of @icp-sdk/core, to type check and run the generated actors without a replica.
+// The values are encoded as JSON instead of Candid, which is enough to check the actor wrappers.
+
+const PACKAGE_JSON = {
+  name: '@icp-sdk/core',
+  type: 'module',
+  exports: Object.fromEntries(
+    ['agent', 'candid', 'principal'].map((module) => [
+      `./${module}`,
+      { types: `./${module}.d.ts`, default: `./${module}.js` },
+    ]),
+  ),
+};
+
+const PRINCIPAL_TYPES = `
+export declare class Principal {
+  static from(other: unknown): Principal;
+  static fromText(text: string): Principal;
+  toText(): string;
+  toString(): string;
+}
+`;
+
+const PRINCIPAL_RUNTIME = `
+export class Principal {
+  constructor(text) {
+    this.text = text;
+  }
+  static from(other) {
+    return other instanceof Principal ? other : new Principal(String(other));
+  }
+  static fromText(text) {
+    return new Principal(text);
+  }
+  toText() {
+    return this.text;
+  }
+  toString() {
+    return this.text;
+  }
+}
+`;
+
+const CANDID_TYPES = `
+export namespace IDL {
+  interface Type {
+    readonly name: string;
+  }
+  interface RecClass extends Type {
+    fill(t: Type): void;
+  }
+  interface FuncClass extends Type {
+    argTypes: Type[];
+    retTypes: Type[];
+    annotations: string[];
+  }
+  interface ServiceClass extends Type {
+    _fields: Array<[string, FuncClass]>;
+  }
+  type InterfaceFactory = (idl: { IDL: typeof IDL }) => ServiceClass;
+
+  function encode(argTypes: Type[], args: unknown[]): Uint8Array;
+  function decode(retTypes: Type[], bytes: Uint8Array): unknown[];
+
+  function Rec(): RecClass;
+  function Service(methods: Record<string, FuncClass>): ServiceClass;
+  function Func(args: Type[], ret: Type[], annotations?: string[]): FuncClass;
+  function Record(fields: Record<string, Type>): Type;
+  function Variant(fields: Record<string, Type>): Type;
+  function Opt(t: Type): Type;
+  function Vec(t: Type): Type;
+  function Tuple(...ts: Type[]): Type;
+
+  const Nat: Type;
+  const Int: Type;
+  const Text: Type;
+  const Bool: Type;
+  const Null: Type;
+  const Principal: Type;
+  const Nat8: Type;
+  const Nat16: Type;
+  const Nat32: Type;
+  const Nat64: Type;
+  const Int8: Type;
+  const Int16: Type;
+  const Int32: Type;
+  const Int64: Type;
+  const Float32: Type;
+  const Float64: Type;
+  const Empty: Type;
+  const Reserved: Type;
+}
+`;
+
+const CANDID_RUNTIME = `
+import { Principal } from './principal.js';
+
+function replacer(_key, value) {
+  if (typeof value === 'bigint') {
+    return { __bigint__: value.toString() };
+  }
+  if (value instanceof Principal) {
+    return { __principal__: value.toText() };
+  }
+  return value;
+}
+
+function reviver(_key, value) {
+  if (typeof value === 'object' && value !== null && '__bigint__' in value) {
+    return BigInt(value.__bigint__);
+  }
+  if (typeof value === 'object' && value !== null && '__principal__' in value) {
+    return Principal.fromText(value.__principal__);
+  }
+  return value;
+}
+
+const primitive = (name) => ({ name });
+
+export const IDL = {
+  encode: (_argTypes, args) => new TextEncoder().encode(JSON.stringify(args, replacer)),
+  decode: (_retTypes, bytes) => JSON.parse(new TextDecoder().decode(bytes), reviver),
+  Rec: () => ({ name: 'rec', fill() {} }),
+  Service: (methods) => ({ name: 'service', _fields: Object.entries(methods) }),
+  Func: (argTypes, retTypes, annotations = []) => ({ name: 'func', argTypes, retTypes, annotations }),
+  Record: (fields) => ({ name: 'record', fields }),
+  Variant: (fields) => ({ name: 'variant', fields }),
+  Opt: (type) => ({ name: 'opt', type }),
+  Vec: (type) => ({ name: 'vec', type }),
+  Tuple: (...types) => ({ name: 'tuple', types }),
+  Nat: primitive('nat'),
+  Int: primitive('int'),
+  Text: primitive('text'),
+  Bool: primitive('bool'),
+  Null: primitive('null'),
+  Principal: primitive('principal'),
+  Nat8: primitive('nat8'),
+  Nat16: primitive('nat16'),
+  Nat32: primitive('nat32'),
+  Nat64: primitive('nat64'),
+  Int8: primitive('int8'),
+  Int16: primitive('int16'),
+  Int32: primitive('int32'),
+  Int64: primitive('int64'),
+  Float32: primitive('float32'),
+  Float64: primitive('float64'),
+  Empty: primitive('empty'),
+  Reserved: primitive('reserved'),
+};
+`;
+
+const AGENT_TYPES = `
+import type { IDL } from './candid';
+import type { Principal } from './principal';
+
+export type RequestId = ArrayBuffer & { __requestId__: void };
+
+export interface SubmitResponse {
+  requestId: RequestId;
+  response: { ok: boolean; status: number; statusText: string };
+}
+
+export interface CallOptions {
+  methodName: string;
+  arg: Uint8Array;
+  effectiveCanisterId: Principal | string;
+  callSync?: boolean;
+}
+
+export interface Agent {
+  call(canisterId: Principal | string, options: CallOptions): Promise<SubmitResponse>;
+}
+
+export interface HttpAgentOptions {
+  host?: string;
+}
+
+export declare class HttpAgent implements Agent {
+  static createSync(options?: HttpAgentOptions): HttpAgent;
+  call(canisterId: Principal | string, options: CallOptions): Promise<SubmitResponse>;
+}
+
+export interface PollingOptions {
+  maxIterations?: number;
+}
+
+export interface CallConfig {
+  agent?: Agent;
+  pollingOptions?: PollingOptions;
+  canisterId?: string | Principal;
+  effectiveCanisterId?: Principal;
+}
+
+export interface ActorConfig extends CallConfig {
+  canisterId: string | Principal;
+  callTransform?: (methodName: string, args: unknown[], callConfig: CallConfig) => Partial<CallConfig> | void;
+  queryTransform?: (methodName: string, args: unknown[], callConfig: CallConfig) => Partial<CallConfig> | void;
+}
+
+export interface ActorMethod<Args extends unknown[] = unknown[], Ret = unknown> {
+  (...args: Args): Promise<Ret>;
+  withOptions(options: CallConfig): (...args: Args) => Promise<Ret>;
+}
+
+export type ActorSubclass<T = Record<string, ActorMethod>> = Actor & T;
+
+export declare class Actor {
+  protected constructor(metadata: unknown);
+  static agentOf(actor: Actor): Agent | undefined;
+  static canisterIdOf(actor: Actor): Principal;
+  static interfaceOf(actor: Actor): IDL.ServiceClass;
+  static createActor<T = Record<string, ActorMethod>>(
+    interfaceFactory: IDL.InterfaceFactory,
+    configuration: ActorConfig,
+  ): ActorSubclass<T>;
+}
+`;
+
+// The calls of the actors are handled by the \`handle\` method of their agent, with the decoded arguments.
+const AGENT_RUNTIME = `
+import { IDL } from './candid.js';
+import { Principal } from './principal.js';
+
+const METADATA = Symbol('metadata');
+
+function call_mode(func) {
+  return ['query', 'composite_query', 'oneway'].find((mode) => func.annotations.includes(mode)) ?? 'update';
+}
+
+export class Actor {
+  static agentOf(actor) {
+    return actor[METADATA].config.agent;
+  }
+  static canisterIdOf(actor) {
+    return Principal.from(actor[METADATA].config.canisterId);
+  }
+  static interfaceOf(actor) {
+    return actor[METADATA].service;
+  }
+  static createActor(interfaceFactory, config) {
+    const service = interfaceFactory({ IDL });
+    const actor = new Actor();
+    actor[METADATA] = { service, config };
+    for (const [methodName, func] of service._fields) {
+      const caller = (options = {}) => async (...args) => {
+        const { agent, canisterId, effectiveCanisterId } = { ...config, ...options };
+        const result = await agent.handle({
+          methodName,
+          mode: call_mode(func),
+          args: IDL.decode(func.argTypes, IDL.encode(func.argTypes, args)),
+          canisterId: Principal.from(canisterId).toText(),
+          effectiveCanisterId: effectiveCanisterId && Principal.from(effectiveCanisterId).toText(),
+        });
+        const values = func.retTypes.length === 0 ? [] : func.retTypes.length === 1 ? [result] : result;
+        const decoded = IDL.decode(func.retTypes, IDL.encode(func.retTypes, values));
+        return decoded.length === 0 ? undefined : decoded.length === 1 ? decoded[0] : decoded;
+      };
+      actor[methodName] = Object.assign(caller(), { withOptions: caller });
+    }
+    return actor;
+  }
+}
+
+export class HttpAgent {
+  static createSync() {
+    return new HttpAgent();
+  }
+  async handle() {
+    throw new Error('The stub HttpAgent cannot make calls');
+  }
+  async call() {
+    throw new Error('The stub HttpAgent cannot make calls');
+  }
+}
+`;
+
+/**
+ * Writes the stubs of @icp-sdk/core to `node_modules` in `dir`.
+ */
+export function writeCoreStubs(dir: string): void {
+  const coreDir = join(dir, 'node_modules', '@icp-sdk', 'core');
+  mkdirSync(coreDir, { recursive: true });
+  writeFileSync(join(coreDir, 'package.json'), JSON.stringify(PACKAGE_JSON));
+  writeFileSync(join(coreDir, 'principal.d.ts'), PRINCIPAL_TYPES);
+  writeFileSync(join(coreDir, 'principal.js'), PRINCIPAL_RUNTIME);
+  writeFileSync(join(coreDir, 'candid.d.ts'), CANDID_TYPES);
+  writeFileSync(join(coreDir, 'candid.js'), CANDID_RUNTIME);
+  writeFileSync(join(coreDir, 'agent.d.ts'), AGENT_TYPES);
+  writeFileSync(join(coreDir, 'agent.js'), AGENT_RUNTIME);
+}